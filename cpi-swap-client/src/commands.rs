@@ -0,0 +1,209 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    message::{v0::Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::VersionedTransaction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use std::sync::Arc;
+
+use crate::helpers::get_discriminator;
+use crate::retryable_rpc::RetryableRpcClient;
+use crate::CPI_SWAP_PROGRAM_ID;
+
+/// Mirrors `CloseRemainder` in the program.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub enum CloseRemainder {
+    Reject,
+    Withdraw,
+    Burn,
+}
+
+impl CloseRemainder {
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "reject" => CloseRemainder::Reject,
+            "withdraw" => CloseRemainder::Withdraw,
+            "burn" => CloseRemainder::Burn,
+            _ => panic!("Unknown remainder {value}, expected reject, withdraw or burn"),
+        }
+    }
+}
+
+/// Closes the strategy vault's ATA for `mint` and sends the rent to
+/// `destination`. With `CloseRemainder::Withdraw` the balance goes to the
+/// admin's own ATA.
+pub async fn close_vault_token_account(
+    rpc_client: &Arc<RpcClient>,
+    rpc_url: &str,
+    admin: &Keypair,
+    strategy_id: u64,
+    mint: Pubkey,
+    destination: Pubkey,
+    remainder: CloseRemainder,
+) {
+    let admin_pubkey = admin.pubkey();
+    let token_program = rpc_client
+        .get_account(&mint)
+        .expect("Failed to fetch mint")
+        .owner;
+
+    let (strategy, _) = Pubkey::find_program_address(
+        &[b"strategy", &strategy_id.to_le_bytes()],
+        &CPI_SWAP_PROGRAM_ID,
+    );
+    let (vault, _) = Pubkey::find_program_address(
+        &[b"vault", &strategy_id.to_le_bytes()],
+        &CPI_SWAP_PROGRAM_ID,
+    );
+    let (config, _) = Pubkey::find_program_address(&[b"config"], &CPI_SWAP_PROGRAM_ID);
+    let (event_authority, _) =
+        Pubkey::find_program_address(&[b"__event_authority"], &CPI_SWAP_PROGRAM_ID);
+    let vault_token_account =
+        get_associated_token_address_with_program_id(&vault, &mint, &token_program);
+    println!("Closing vault token account {}", vault_token_account);
+
+    let mut instructions = vec![];
+    let withdraw_token_account_meta = match remainder {
+        CloseRemainder::Withdraw => {
+            instructions.push(create_associated_token_account_idempotent(
+                &admin_pubkey,
+                &admin_pubkey,
+                &mint,
+                &token_program,
+            ));
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(&admin_pubkey, &mint, &token_program),
+                false,
+            )
+        }
+        // Anchor expects the program id in place of an omitted optional account
+        _ => AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),
+    };
+
+    // Burning needs a writable mint, and so does harvesting any transfer fees
+    // Token-2022 withheld in the account before it can be closed
+    let mint_meta =
+        match matches!(remainder, CloseRemainder::Burn) || token_program != spl_token::ID {
+            true => AccountMeta::new(mint, false),
+            false => AccountMeta::new_readonly(mint, false),
+        };
+
+    let mut data = Vec::from(get_discriminator("global:close_vault_token_account"));
+    remainder.serialize(&mut data).unwrap();
+
+    instructions.push(Instruction {
+        program_id: CPI_SWAP_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(admin_pubkey, true), // admin
+            AccountMeta::new_readonly(config, false),      // config
+            AccountMeta::new_readonly(strategy, false),    // strategy
+            AccountMeta::new_readonly(vault, false),       // vault
+            mint_meta,                                     // mint
+            AccountMeta::new_readonly(token_program, false), // token program
+            AccountMeta::new(vault_token_account, false),  // vault token account
+            withdraw_token_account_meta,                   // withdraw token account
+            AccountMeta::new(destination, false),          // destination
+            AccountMeta::new_readonly(event_authority, false), // event authority
+            AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false), // program
+        ],
+        data,
+    });
+
+    send_admin_transaction(rpc_client, rpc_url, admin, &instructions).await;
+}
+
+/// Moves the legacy vault's whole balance of `mint` into the strategy vault's
+/// ATA, creating the ATA if needed.
+pub async fn migrate_legacy_vault(
+    rpc_client: &Arc<RpcClient>,
+    rpc_url: &str,
+    admin: &Keypair,
+    strategy_id: u64,
+    mint: Pubkey,
+) {
+    let admin_pubkey = admin.pubkey();
+    let token_program = rpc_client
+        .get_account(&mint)
+        .expect("Failed to fetch mint")
+        .owner;
+
+    let (strategy, _) = Pubkey::find_program_address(
+        &[b"strategy", &strategy_id.to_le_bytes()],
+        &CPI_SWAP_PROGRAM_ID,
+    );
+    let (vault, _) = Pubkey::find_program_address(
+        &[b"vault", &strategy_id.to_le_bytes()],
+        &CPI_SWAP_PROGRAM_ID,
+    );
+    let (legacy_vault, _) = Pubkey::find_program_address(&[b"vault"], &CPI_SWAP_PROGRAM_ID);
+    let (config, _) = Pubkey::find_program_address(&[b"config"], &CPI_SWAP_PROGRAM_ID);
+    let (event_authority, _) =
+        Pubkey::find_program_address(&[b"__event_authority"], &CPI_SWAP_PROGRAM_ID);
+    let legacy_vault_token_account =
+        get_associated_token_address_with_program_id(&legacy_vault, &mint, &token_program);
+    let vault_token_account =
+        get_associated_token_address_with_program_id(&vault, &mint, &token_program);
+    println!(
+        "Migrating {} from {} to {}",
+        mint, legacy_vault_token_account, vault_token_account
+    );
+
+    let instructions = vec![
+        create_associated_token_account_idempotent(&admin_pubkey, &vault, &mint, &token_program),
+        Instruction {
+            program_id: CPI_SWAP_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(admin_pubkey, true),  // admin
+                AccountMeta::new_readonly(config, false),       // config
+                AccountMeta::new_readonly(strategy, false),     // strategy
+                AccountMeta::new_readonly(vault, false),        // vault
+                AccountMeta::new_readonly(legacy_vault, false), // legacy vault
+                AccountMeta::new_readonly(mint, false),         // mint
+                AccountMeta::new_readonly(token_program, false), // token program
+                AccountMeta::new(legacy_vault_token_account, false), // legacy vault token account
+                AccountMeta::new(vault_token_account, false),   // vault token account
+                AccountMeta::new_readonly(event_authority, false), // event authority
+                AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false), // program
+            ],
+            data: Vec::from(get_discriminator("global:migrate_legacy_vault")),
+        },
+    ];
+
+    send_admin_transaction(rpc_client, rpc_url, admin, &instructions).await;
+}
+
+async fn send_admin_transaction(
+    rpc_client: &Arc<RpcClient>,
+    rpc_url: &str,
+    admin: &Keypair,
+    instructions: &[Instruction],
+) {
+    let recent_blockhash = rpc_client
+        .get_latest_blockhash()
+        .expect("Failed to fetch blockhash");
+    let message =
+        Message::try_compile(&admin.pubkey(), instructions, &[], recent_blockhash).unwrap();
+    let tx = VersionedTransaction::try_new(VersionedMessage::V0(message), &[admin]).unwrap();
+    let tx_hash = tx.signatures[0];
+
+    let retryable_client = RetryableRpcClient::new(rpc_url);
+    if let Ok(tx_hash) = retryable_client.send_and_confirm_transaction(&tx).await {
+        println!(
+            "Transaction confirmed: https://explorer.solana.com/tx/{}",
+            tx_hash
+        );
+    } else {
+        println!(
+            "Transaction failed: https://explorer.solana.com/tx/{}",
+            tx_hash
+        );
+    }
+}
@@ -1,10 +1,13 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use sha2::{Digest, Sha256};
 use solana_client::client_error::ClientError;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::address_lookup_table::state::AddressLookupTable;
 use solana_sdk::address_lookup_table::AddressLookupTableAccount;
 use solana_sdk::pubkey::Pubkey;
+use std::env;
+use std::fmt::Display;
+use std::str::FromStr;
 use std::sync::Arc;
 
 #[allow(dead_code)]
@@ -36,3 +39,19 @@ pub fn get_discriminator(name: &str) -> [u8; 8] {
     discriminator.copy_from_slice(&result[..8]);
     discriminator
 }
+
+/// Reads an optional setting from the environment. Unset and empty values are
+/// `None`, so `.env` files can leave a setting blank.
+pub fn optional_env<T>(name: &str) -> Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    match env::var(name) {
+        Ok(value) if !value.is_empty() => value
+            .parse()
+            .map(Some)
+            .map_err(|err| anyhow!("Failed to parse {name} {value:?}: {err}")),
+        _ => Ok(None),
+    }
+}
@@ -1,9 +1,11 @@
+mod commands;
 mod helpers;
 mod retryable_rpc;
 
+use anyhow::Context;
 use base64::engine::Engine;
 use borsh::{BorshDeserialize, BorshSerialize};
-use helpers::{get_address_lookup_table_accounts, get_discriminator};
+use helpers::{get_address_lookup_table_accounts, get_discriminator, optional_env};
 use jup_swap::{
     quote::QuoteRequest,
     swap::SwapRequest,
@@ -20,6 +22,7 @@ use solana_sdk::{
     pubkey::Pubkey,
     signature::Keypair,
     signer::Signer,
+    system_program,
     transaction::VersionedTransaction,
 };
 use spl_associated_token_account::{
@@ -27,6 +30,7 @@ use spl_associated_token_account::{
 };
 use spl_token::ID as TOKEN_PROGRAM_ID;
 use std::env;
+use std::str::FromStr;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
@@ -40,7 +44,7 @@ const INPUT_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112"
 const INPUT_AMOUNT: u64 = 20;
 const OUTPUT_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
 
-const CPI_SWAP_PROGRAM_ID: Pubkey = pubkey!("8KQG1MYXru73rqobftpFjD3hBD8Ab3jaag8wbjZG63sx");
+pub const CPI_SWAP_PROGRAM_ID: Pubkey = pubkey!("8KQG1MYXru73rqobftpFjD3hBD8Ab3jaag8wbjZG63sx");
 const JUPITER_PROGRAM_ID: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
 const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
 
@@ -55,7 +59,7 @@ pub struct SwapIxData {
 }
 
 #[tokio::main]
-async fn main() {
+async fn main() -> anyhow::Result<()> {
     let rpc_url = DEFAULT_RPC_URL.to_string();
 
     let keypair_str = "[152,88,152,250,220,149,198,252,107,48,197,42,115,172,246,149,42,193,252,79,103,137,40,140,9,160,233,137,127,159,34,158,55,116,254,213,22,206,79,12,64,21,244,25,61,56,175,11,177,61,12,84,191,245,22,151,52,155,156,166,109,122,166,89]";
@@ -73,6 +77,47 @@ async fn main() {
         CommitmentConfig::confirmed(),
     ));
 
+    // Each strategy has its own vault derived from its id
+    let strategy_id: u64 = optional_env("STRATEGY_ID")?.unwrap_or(0);
+
+    // cpi-swap-client close-token-account <MINT> [DESTINATION] [reject|withdraw|burn]
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("close-token-account") {
+        let mint = args
+            .get(2)
+            .map(|key| Pubkey::from_str(key).expect("Failed to parse MINT"))
+            .expect("Usage: close-token-account <MINT> [DESTINATION] [reject|withdraw|burn]");
+        let destination = args
+            .get(3)
+            .map(|key| Pubkey::from_str(key).expect("Failed to parse DESTINATION"))
+            .unwrap_or(keypair_pubkey);
+        let remainder = args
+            .get(4)
+            .map(|value| commands::CloseRemainder::parse(value))
+            .unwrap_or(commands::CloseRemainder::Reject);
+        commands::close_vault_token_account(
+            &rpc_client,
+            &rpc_url,
+            &keypair,
+            strategy_id,
+            mint,
+            destination,
+            remainder,
+        )
+        .await;
+        return Ok(());
+    }
+
+    // cpi-swap-client migrate-legacy-vault <MINT>
+    if args.get(1).map(String::as_str) == Some("migrate-legacy-vault") {
+        let mint = args
+            .get(2)
+            .map(|key| Pubkey::from_str(key).expect("Failed to parse MINT"))
+            .expect("Usage: migrate-legacy-vault <MINT>");
+        commands::migrate_legacy_vault(&rpc_client, &rpc_url, &keypair, strategy_id, mint).await;
+        return Ok(());
+    }
+
     let rpc_client_clone = rpc_client.clone();
     let latest_blockhash = Arc::new(LatestBlockhash {
         blockhash: RwLock::new(solana_sdk::hash::Hash::default()),
@@ -97,10 +142,27 @@ async fn main() {
 
     let jupiter_swap_api_client = JupiterSwapApiClient::new(api_base_url);
 
+    // Partner flow charges a platform fee into our referral token account
+    let platform_fee_account: Option<Pubkey> = optional_env("PLATFORM_FEE_ACCOUNT")?;
+    let platform_fee_bps: Option<u8> = match platform_fee_account {
+        Some(_) => Some(
+            optional_env("PLATFORM_FEE_BPS")?
+                .context("PLATFORM_FEE_BPS is required with PLATFORM_FEE_ACCOUNT")?,
+        ),
+        None => None,
+    };
+
+    // Have the vault pay back this operator's transaction fees
+    let reimburse_fees = env::var("REIMBURSE_FEES").is_ok_and(|value| value == "true");
+
+    // Optionally forward the swap output, e.g. to pay an invoice
+    let recipient_token_account: Option<Pubkey> = optional_env("RECIPIENT_TOKEN_ACCOUNT")?;
+
     let quote_request = QuoteRequest {
         amount: INPUT_AMOUNT,
         input_mint: INPUT_MINT,
         output_mint: OUTPUT_MINT,
+        platform_fee_bps,
         ..QuoteRequest::default()
     };
 
@@ -115,11 +177,58 @@ async fn main() {
         }
         Err(e) => {
             println!("quote failed: {e:#?}");
-            return;
+            return Ok(());
         }
     };
 
-    let (vault, _) = Pubkey::find_program_address(&[b"vault"], &CPI_SWAP_PROGRAM_ID);
+    let (strategy, _) = Pubkey::find_program_address(
+        &[b"strategy", &strategy_id.to_le_bytes()],
+        &CPI_SWAP_PROGRAM_ID,
+    );
+    let (vault, _) = Pubkey::find_program_address(
+        &[b"vault", &strategy_id.to_le_bytes()],
+        &CPI_SWAP_PROGRAM_ID,
+    );
+    let (config, _) = Pubkey::find_program_address(&[b"config"], &CPI_SWAP_PROGRAM_ID);
+    let (input_volume_limit, _) = Pubkey::find_program_address(
+        &[b"volume_limit", vault.as_ref(), INPUT_MINT.as_ref()],
+        &CPI_SWAP_PROGRAM_ID,
+    );
+    let (operator_volume, _) = Pubkey::find_program_address(
+        &[
+            b"operator_volume",
+            input_volume_limit.as_ref(),
+            keypair_pubkey.as_ref(),
+        ],
+        &CPI_SWAP_PROGRAM_ID,
+    );
+    let (vault_stats, _) =
+        Pubkey::find_program_address(&[b"vault_stats", vault.as_ref()], &CPI_SWAP_PROGRAM_ID);
+    let (aggregator_registry, _) =
+        Pubkey::find_program_address(&[b"aggregator_registry"], &CPI_SWAP_PROGRAM_ID);
+    let (route_policy, _) =
+        Pubkey::find_program_address(&[b"route_policy", vault.as_ref()], &CPI_SWAP_PROGRAM_ID);
+    // Anchor expects the program id in place of an omitted optional account
+    let platform_fee_account_key = platform_fee_account.unwrap_or(CPI_SWAP_PROGRAM_ID);
+    let (fee_reimbursement, _) = Pubkey::find_program_address(
+        &[b"fee_reimbursement", vault.as_ref()],
+        &CPI_SWAP_PROGRAM_ID,
+    );
+    let fee_reimbursement_meta = match reimburse_fees {
+        true => AccountMeta::new(fee_reimbursement, false),
+        false => AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),
+    };
+    // Mints without a volume limit are uncapped and need no operator volume
+    let operator_volume_meta = match rpc_client.get_account(&input_volume_limit) {
+        Ok(_) => AccountMeta::new(operator_volume, false),
+        Err(_) => AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),
+    };
+    let recipient_token_account_meta = match recipient_token_account {
+        Some(recipient_token_account) => AccountMeta::new(recipient_token_account, false),
+        None => AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),
+    };
+    let (event_authority, _) =
+        Pubkey::find_program_address(&[b"__event_authority"], &CPI_SWAP_PROGRAM_ID);
 
     let response = jupiter_swap_api_client
         .swap_instructions(&SwapRequest {
@@ -128,6 +237,7 @@ async fn main() {
             config: TransactionConfig {
                 skip_user_accounts_rpc_calls: true,
                 wrap_and_unwrap_sol: false,
+                fee_account: platform_fee_account,
                 dynamic_compute_unit_limit: true,
                 dynamic_slippage: Some(DynamicSlippageSettings {
                     min_bps: Some(50),
@@ -167,14 +277,30 @@ async fn main() {
     instruction_data.serialize(&mut serialized_data).unwrap();
 
     let mut accounts = vec![
-        AccountMeta::new_readonly(INPUT_MINT, false), // input mint
+        AccountMeta::new(keypair_pubkey, true),             // operator
+        AccountMeta::new_readonly(config, false),           // config
+        AccountMeta::new_readonly(INPUT_MINT, false),       // input mint
         AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false), // input mint program (for now, just hardcoded to SPL and not SPL 2022)
         AccountMeta::new_readonly(OUTPUT_MINT, false),      // output mint
         AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false), // output mint program (for now, just hardcoded to SPL and not SPL 2022)
+        AccountMeta::new_readonly(strategy, false),         // strategy
         AccountMeta::new(vault, false),                     // vault
         AccountMeta::new(input_token_account, false),       // vault input token account
         AccountMeta::new(output_token_account, false),      // vault output token account
-        AccountMeta::new_readonly(JUPITER_PROGRAM_ID, false), // jupiter program
+        AccountMeta::new_readonly(platform_fee_account_key, false), // platform fee account
+        recipient_token_account_meta,                       // recipient token account
+        AccountMeta::new(input_volume_limit, false),        // input volume limit
+        operator_volume_meta,                               // operator volume
+        AccountMeta::new(vault_stats, false),               // vault stats
+        fee_reimbursement_meta,                             // fee reimbursement
+        AccountMeta::new_readonly(aggregator_registry, false), // aggregator registry
+        AccountMeta::new_readonly(route_policy, false),     // route policy
+        AccountMeta::new_readonly(JUPITER_PROGRAM_ID, false), // aggregator program
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false), // hook registry (unused by swap)
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false), // hook program (unused by swap)
+        AccountMeta::new_readonly(system_program::ID, false), // system program
+        AccountMeta::new_readonly(event_authority, false),  // event authority
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false), // program
     ];
     let remaining_accounts = response.swap_instruction.accounts;
     accounts.extend(remaining_accounts.into_iter().map(|mut account| {
@@ -270,6 +396,8 @@ async fn main() {
             "Transaction failed: https://explorer.solana.com/tx/{}",
             tx_hash
         );
-        return;
+        return Ok(());
     };
+
+    Ok(())
 }
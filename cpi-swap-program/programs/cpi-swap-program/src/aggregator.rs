@@ -0,0 +1,473 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, program::invoke_signed},
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{
+    constants::VAULT_SEED,
+    errors::CpiSwapError,
+    route_plan::DecodedRoute,
+    state::{AggregatorRegistry, RouteDecoder, RoutePolicy, StrategyConfig},
+    utils::{token_account_authorities, unpack_token_account},
+};
+
+/// Checks `data` against the aggregator registry and the route policy, and
+/// returns the decoded route when the aggregator is registered with a
+/// decoder.
+pub fn validate_route(
+    aggregator_registry: &AggregatorRegistry,
+    route_policy: &RoutePolicy,
+    aggregator_program: &Pubkey,
+    data: &[u8],
+) -> Result<Option<DecodedRoute>> {
+    let route = match aggregator_registry.check(aggregator_program, data)? {
+        RouteDecoder::JupiterV6 => Some(DecodedRoute::decode(data)?),
+        RouteDecoder::None => None,
+    };
+    route_policy.check(route.as_ref())?;
+
+    Ok(route)
+}
+
+/// Referral fee account and rate a route's platform fee must match.
+pub struct PlatformFee {
+    pub account: Pubkey,
+    pub bps: u8,
+    /// Owner the fee account must have.
+    pub referral_authority: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+}
+
+/// A route may only charge a platform fee at the configured rate and into
+/// the declared referral fee account, which must be owned by the referral
+/// authority and hold the mint the route charges the fee in.
+pub fn check_platform_fee(
+    route: Option<&DecodedRoute>,
+    remaining_accounts: &[AccountInfo],
+    platform_fee: Option<&PlatformFee>,
+) -> Result<()> {
+    let Some(route) = route else {
+        return Ok(());
+    };
+
+    match platform_fee {
+        Some(platform_fee) => {
+            require_eq!(
+                route.platform_fee_bps,
+                platform_fee.bps,
+                CpiSwapError::PlatformFeeMismatch
+            );
+            let route_fee_account = remaining_accounts
+                .get(route.platform_fee_account_index)
+                .ok_or(CpiSwapError::InvalidPlatformFeeAccount)?;
+            require_keys_eq!(
+                *route_fee_account.key,
+                platform_fee.account,
+                CpiSwapError::InvalidPlatformFeeAccount
+            );
+
+            let fee_account = unpack_token_account(route_fee_account)
+                .ok_or(CpiSwapError::InvalidPlatformFeeAccount)?;
+            let fee_mint = match route.exact_out {
+                true => platform_fee.input_mint,
+                false => platform_fee.output_mint,
+            };
+            require!(
+                fee_account.owner == platform_fee.referral_authority
+                    && fee_account.mint == fee_mint,
+                CpiSwapError::InvalidPlatformFeeAccount
+            );
+        }
+        None => require_eq!(route.platform_fee_bps, 0, CpiSwapError::PlatformFeeMismatch),
+    }
+
+    Ok(())
+}
+
+/// A route may only deliver its output into `destination`. Jupiter takes its
+/// own program id in place of an omitted optional account.
+pub fn check_route_destination(
+    route: &DecodedRoute,
+    remaining_accounts: &[AccountInfo],
+    destination: &Pubkey,
+    aggregator_program: &Pubkey,
+) -> Result<()> {
+    let account_key = |index: usize| -> Result<Pubkey> {
+        Ok(*remaining_accounts
+            .get(index)
+            .ok_or(CpiSwapError::InvalidRouteDestination)?
+            .key)
+    };
+
+    require_keys_eq!(
+        account_key(route.destination_account_index)?,
+        *destination,
+        CpiSwapError::InvalidRouteDestination
+    );
+    if let Some(index) = route.destination_override_index {
+        let destination_override = account_key(index)?;
+        require!(
+            destination_override == *destination || destination_override == *aggregator_program,
+            CpiSwapError::InvalidRouteDestination
+        );
+    }
+
+    Ok(())
+}
+
+/// When the vault signs for a route, the only token accounts the route may
+/// write to that the vault owns or is delegate of are the `declared` ones.
+pub fn check_vault_writable_accounts(
+    remaining_accounts: &[AccountInfo],
+    vault: &Pubkey,
+    declared: &[Pubkey],
+) -> Result<()> {
+    for acc in remaining_accounts.iter().filter(|acc| acc.is_writable) {
+        if declared.contains(acc.key) {
+            continue;
+        }
+        if let Some((owner, delegate)) = token_account_authorities(acc) {
+            require!(
+                owner != *vault && delegate != Some(*vault),
+                CpiSwapError::UnexpectedVaultTokenAccount
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Invokes the aggregator with `remaining_accounts`. With a `pda_signer` only
+/// that account is marked as a signer and `signer_seeds` sign for it;
+/// otherwise the caller's signer flags are passed through unchanged.
+pub fn invoke_route(
+    aggregator_program: Pubkey,
+    remaining_accounts: &[AccountInfo],
+    data: Vec<u8>,
+    pda_signer: Option<&Pubkey>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let accounts: Vec<AccountMeta> = remaining_accounts
+        .iter()
+        .map(|acc| {
+            let is_signer = match pda_signer {
+                Some(pda_signer) => acc.key == pda_signer,
+                None => acc.is_signer,
+            };
+            AccountMeta {
+                pubkey: *acc.key,
+                is_signer,
+                is_writable: acc.is_writable,
+            }
+        })
+        .collect();
+
+    let accounts_infos: Vec<AccountInfo> = remaining_accounts
+        .iter()
+        .map(|acc| AccountInfo { ..acc.clone() })
+        .collect();
+
+    invoke_signed(
+        &Instruction {
+            program_id: aggregator_program,
+            accounts,
+            data,
+        },
+        &accounts_infos,
+        signer_seeds,
+    )?;
+
+    Ok(())
+}
+
+/// A route the strategy vault signs for, with everything it is checked
+/// against.
+pub struct VaultRoute<'a, 'info> {
+    pub aggregator_registry: &'a AggregatorRegistry,
+    pub route_policy: &'a RoutePolicy,
+    pub strategy: &'a StrategyConfig,
+    pub aggregator_program: Pubkey,
+    pub vault: AccountInfo<'info>,
+    /// Token accounts owned by or delegated to the vault that the route may
+    /// write to.
+    pub writable: &'a [Pubkey],
+    /// Token account the route must deliver its output into.
+    pub destination: Pubkey,
+    pub platform_fee: Option<PlatformFee>,
+}
+
+impl VaultRoute<'_, '_> {
+    /// Checks the route, invokes it with the vault as signer and returns the
+    /// least output the route accepts. Routes that cannot be decoded are
+    /// rejected, since their destination and output cannot be checked.
+    pub fn invoke(&self, remaining_accounts: &[AccountInfo], data: Vec<u8>) -> Result<u64> {
+        let route = validate_route(
+            self.aggregator_registry,
+            self.route_policy,
+            &self.aggregator_program,
+            &data,
+        )?
+        .ok_or(CpiSwapError::UndecodedRoute)?;
+        self.strategy.check_route(Some(&route))?;
+        check_route_destination(
+            &route,
+            remaining_accounts,
+            &self.destination,
+            &self.aggregator_program,
+        )?;
+        check_platform_fee(Some(&route), remaining_accounts, self.platform_fee.as_ref())?;
+        check_vault_writable_accounts(remaining_accounts, self.vault.key, self.writable)?;
+
+        let vault_lamports_before = self.vault.lamports();
+
+        let strategy_id = self.strategy.strategy_id.to_le_bytes();
+        invoke_route(
+            self.aggregator_program,
+            remaining_accounts,
+            data,
+            Some(self.vault.key),
+            &[&[VAULT_SEED, &strategy_id, &[self.strategy.vault_bump]]],
+        )?;
+
+        require_eq!(
+            vault_lamports_before,
+            self.vault.lamports(),
+            CpiSwapError::VaultLamportsChanged
+        );
+
+        Ok(route.min_amount_out)
+    }
+}
+
+/// Reloads both vault token accounts and returns the realized
+/// `(amount_in, amount_out)` of a vault route against the balances captured
+/// before it. Output that did not land in the vault output token account, for
+/// example because the route pays another destination, fails the swap.
+pub fn settle_vault_route(
+    vault_input_token_account: &mut InterfaceAccount<TokenAccount>,
+    vault_output_token_account: &mut InterfaceAccount<TokenAccount>,
+    input_before: u64,
+    output_before: u64,
+    min_amount_out: u64,
+) -> Result<(u64, u64)> {
+    vault_input_token_account.reload()?;
+    vault_output_token_account.reload()?;
+
+    let amount_in = input_before.saturating_sub(vault_input_token_account.amount);
+    let amount_out = vault_output_token_account
+        .amount
+        .saturating_sub(output_before);
+    require_gte!(
+        amount_out,
+        min_amount_out,
+        CpiSwapError::MinimumOutputNotMet
+    );
+
+    Ok((amount_in, amount_out))
+}
+
+/// Returns the lamports the vault holds above rent exemption.
+pub fn spare_vault_lamports(vault: &AccountInfo) -> Result<u64> {
+    Ok(vault
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(0)))
+}
+
+/// Transfers `lamports` from the strategy vault to `to`, with the vault as
+/// signer.
+pub fn pay_from_vault<'info>(
+    system_program: &Program<'info, System>,
+    vault: &SystemAccount<'info>,
+    strategy: &StrategyConfig,
+    to: AccountInfo<'info>,
+    lamports: u64,
+) -> Result<()> {
+    let strategy_id = strategy.strategy_id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &strategy_id, &[strategy.vault_bump]]];
+    transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to,
+            },
+            signer_seeds,
+        ),
+        lamports,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token_2022::spl_token_2022::state::{Account as SplTokenAccount, AccountState};
+
+    use super::*;
+
+    /// Backing storage for one test `AccountInfo`.
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+        is_writable: bool,
+    }
+
+    impl TestAccount {
+        fn token(mint: Pubkey, owner: Pubkey, delegate: Option<Pubkey>) -> Self {
+            let mut data = vec![0; SplTokenAccount::LEN];
+            SplTokenAccount {
+                mint,
+                owner,
+                delegate: delegate.into(),
+                state: AccountState::Initialized,
+                ..Default::default()
+            }
+            .pack_into_slice(&mut data);
+            Self {
+                key: Pubkey::new_unique(),
+                owner: anchor_spl::token::ID,
+                lamports: 0,
+                data,
+                is_writable: true,
+            }
+        }
+
+        fn other() -> Self {
+            Self {
+                key: Pubkey::new_unique(),
+                owner: Pubkey::default(),
+                lamports: 0,
+                data: vec![],
+                is_writable: true,
+            }
+        }
+
+        fn read_only(mut self) -> Self {
+            self.is_writable = false;
+            self
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                false,
+                self.is_writable,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                false,
+                0,
+            )
+        }
+    }
+
+    fn infos(accounts: &mut [TestAccount]) -> Vec<AccountInfo<'_>> {
+        accounts.iter_mut().map(TestAccount::info).collect()
+    }
+
+    #[test]
+    fn vault_may_only_write_declared_token_accounts() {
+        let vault = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        let mut declared = [
+            TestAccount::token(mint, vault, None),
+            TestAccount::token(mint, Pubkey::new_unique(), None),
+            TestAccount::other(),
+        ];
+        let declared_key = declared[0].key;
+        check_vault_writable_accounts(&infos(&mut declared), &vault, &[declared_key]).unwrap();
+
+        let mut read_only = [TestAccount::token(mint, vault, None).read_only()];
+        check_vault_writable_accounts(&infos(&mut read_only), &vault, &[]).unwrap();
+
+        let mut undeclared = [TestAccount::token(mint, vault, None)];
+        assert!(check_vault_writable_accounts(&infos(&mut undeclared), &vault, &[]).is_err());
+
+        let mut delegated = [TestAccount::token(mint, Pubkey::new_unique(), Some(vault))];
+        assert!(check_vault_writable_accounts(&infos(&mut delegated), &vault, &[]).is_err());
+    }
+
+    #[test]
+    fn route_destination_must_be_the_output_account() {
+        let aggregator_program = Pubkey::new_unique();
+        let mut accounts: Vec<TestAccount> = (0..7).map(|_| TestAccount::other()).collect();
+        let destination = accounts[3].key;
+        accounts[4].key = aggregator_program;
+        let accounts = infos(&mut accounts);
+
+        let route = DecodedRoute::test_route(vec![]);
+        check_route_destination(&route, &accounts, &destination, &aggregator_program).unwrap();
+
+        let mut wrong_index = DecodedRoute::test_route(vec![]);
+        wrong_index.destination_account_index = 2;
+        assert!(check_route_destination(
+            &wrong_index,
+            &accounts,
+            &destination,
+            &aggregator_program
+        )
+        .is_err());
+
+        let mut out_of_range = DecodedRoute::test_route(vec![]);
+        out_of_range.destination_account_index = 7;
+        assert!(check_route_destination(
+            &out_of_range,
+            &accounts,
+            &destination,
+            &aggregator_program
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn platform_fee_account_must_match_owner_and_mint() {
+        let referral_authority = Pubkey::new_unique();
+        let input_mint = Pubkey::new_unique();
+        let output_mint = Pubkey::new_unique();
+        let mut route = DecodedRoute::test_route(vec![]);
+        route.platform_fee_bps = 20;
+
+        let check = |fee_account: TestAccount, route: &DecodedRoute| {
+            let mut accounts: Vec<TestAccount> = (0..6).map(|_| TestAccount::other()).collect();
+            accounts.push(fee_account);
+            let platform_fee = PlatformFee {
+                account: accounts[6].key,
+                bps: 20,
+                referral_authority,
+                input_mint,
+                output_mint,
+            };
+            check_platform_fee(Some(route), &infos(&mut accounts), Some(&platform_fee))
+        };
+
+        check(
+            TestAccount::token(output_mint, referral_authority, None),
+            &route,
+        )
+        .unwrap();
+        assert!(check(
+            TestAccount::token(output_mint, Pubkey::new_unique(), None),
+            &route
+        )
+        .is_err());
+        assert!(check(
+            TestAccount::token(input_mint, referral_authority, None),
+            &route
+        )
+        .is_err());
+        assert!(check(TestAccount::other(), &route).is_err());
+
+        // Exact-out routes charge the fee in the input mint
+        route.exact_out = true;
+        check(
+            TestAccount::token(input_mint, referral_authority, None),
+            &route,
+        )
+        .unwrap();
+    }
+}
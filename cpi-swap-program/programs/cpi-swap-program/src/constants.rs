@@ -0,0 +1,41 @@
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const STRATEGY_SEED: &[u8] = b"strategy";
+pub const VOLUME_LIMIT_SEED: &[u8] = b"volume_limit";
+pub const OPERATOR_VOLUME_SEED: &[u8] = b"operator_volume";
+pub const AGGREGATOR_REGISTRY_SEED: &[u8] = b"aggregator_registry";
+pub const ROUTE_POLICY_SEED: &[u8] = b"route_policy";
+pub const DELEGATION_TERMS_SEED: &[u8] = b"delegation_terms";
+pub const USER_NONCE_SEED: &[u8] = b"user_nonce";
+pub const HOOK_REGISTRY_SEED: &[u8] = b"hook_registry";
+pub const VAULT_STATS_SEED: &[u8] = b"vault_stats";
+pub const FEE_REIMBURSEMENT_SEED: &[u8] = b"fee_reimbursement";
+pub const TWAP_ORDER_SEED: &[u8] = b"twap_order";
+pub const VAULT_FEES_SEED: &[u8] = b"vault_fees";
+pub const SHARE_MINT_SEED: &[u8] = b"share_mint";
+pub const VAULT_NAV_SEED: &[u8] = b"vault_nav";
+
+pub const MAX_MANAGERS: usize = 10;
+pub const MAX_KEEPERS: usize = 10;
+pub const MAX_RECIPIENTS: usize = 8;
+pub const MAX_STRATEGY_MINTS: usize = 16;
+pub const MAX_AGGREGATORS: usize = 4;
+pub const MAX_AGGREGATOR_DISCRIMINATORS: usize = 8;
+pub const MAX_POLICY_VENUES: usize = 32;
+pub const MAX_HOOKS: usize = 4;
+pub const MAX_HOOK_DISCRIMINATORS: usize = 8;
+pub const MAX_HOOK_WRITABLE_ACCOUNTS: usize = 8;
+pub const MAX_NAV_ASSETS: usize = 10;
+// Keeps `VaultStats` within the 1024 byte return data limit
+pub const MAX_STATS_MINTS: usize = 12;
+
+pub const PRICE_SCALE: u128 = 1_000_000_000;
+pub const SHARE_DECIMALS: u8 = 9;
+/// Leftover of an order tranche, in basis points of the tranche, too small to
+/// route on its own. A tranche closes once no more than this is left.
+pub const FILL_DUST_BPS: u64 = 10;
+/// Decimals of the NAV in the oracles' quote currency.
+pub const NAV_DECIMALS: u8 = 6;
+
+pub const REIMBURSEMENT_WINDOW_SECONDS: i64 = 86_400;
+pub const SECONDS_PER_YEAR: i64 = 31_536_000;
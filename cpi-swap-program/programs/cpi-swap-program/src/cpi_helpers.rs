@@ -0,0 +1,225 @@
+//! Helpers for programs that compose with the vault over CPI.
+//!
+//! The generated [`crate::cpi`] module has a typed account struct for every
+//! instruction under [`crate::cpi::accounts`]. This module fills in the rest:
+//! PDA derivations for those structs, the Jupiter remaining accounts and
+//! wrappers that hand back the realized swap result.
+//!
+//! ```ignore
+//! let result = cpi_swap_program::cpi_helpers::swap(
+//!     CpiContext::new(vault_program, cpi_swap_program::cpi::accounts::Swap { .. }),
+//!     jupiter_accounts,
+//!     jupiter_instruction_data,
+//! )?;
+//! msg!("received {}", result.amount_out);
+//! ```
+
+use anchor_lang::prelude::*;
+
+pub use crate::return_data::*;
+use crate::{
+    constants::{
+        AGGREGATOR_REGISTRY_SEED, CONFIG_SEED, DELEGATION_TERMS_SEED, FEE_REIMBURSEMENT_SEED,
+        HOOK_REGISTRY_SEED, OPERATOR_VOLUME_SEED, ROUTE_POLICY_SEED, SHARE_MINT_SEED,
+        STRATEGY_SEED, TWAP_ORDER_SEED, USER_NONCE_SEED, VAULT_FEES_SEED, VAULT_NAV_SEED,
+        VAULT_SEED, VAULT_STATS_SEED, VOLUME_LIMIT_SEED,
+    },
+    SwapExactOutResult, SwapResult,
+};
+
+pub fn strategy_address(strategy_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[STRATEGY_SEED, &strategy_id.to_le_bytes()], &crate::ID).0
+}
+
+pub fn vault_address(strategy_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[VAULT_SEED, &strategy_id.to_le_bytes()], &crate::ID).0
+}
+
+pub fn config_address() -> Pubkey {
+    Pubkey::find_program_address(&[CONFIG_SEED], &crate::ID).0
+}
+
+pub fn aggregator_registry_address() -> Pubkey {
+    Pubkey::find_program_address(&[AGGREGATOR_REGISTRY_SEED], &crate::ID).0
+}
+
+pub fn hook_registry_address() -> Pubkey {
+    Pubkey::find_program_address(&[HOOK_REGISTRY_SEED], &crate::ID).0
+}
+
+pub fn route_policy_address(vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ROUTE_POLICY_SEED, vault.as_ref()], &crate::ID).0
+}
+
+pub fn vault_stats_address(vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[VAULT_STATS_SEED, vault.as_ref()], &crate::ID).0
+}
+
+pub fn fee_reimbursement_address(vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[FEE_REIMBURSEMENT_SEED, vault.as_ref()], &crate::ID).0
+}
+
+pub fn vault_fees_address(vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[VAULT_FEES_SEED, vault.as_ref()], &crate::ID).0
+}
+
+pub fn share_mint_address(vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[SHARE_MINT_SEED, vault.as_ref()], &crate::ID).0
+}
+
+pub fn vault_nav_address(vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[VAULT_NAV_SEED, vault.as_ref()], &crate::ID).0
+}
+
+pub fn volume_limit_address(vault: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[VOLUME_LIMIT_SEED, vault.as_ref(), mint.as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
+pub fn operator_volume_address(volume_limit: &Pubkey, operator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            OPERATOR_VOLUME_SEED,
+            volume_limit.as_ref(),
+            operator.as_ref(),
+        ],
+        &crate::ID,
+    )
+    .0
+}
+
+pub fn twap_order_address(vault: &Pubkey, order_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[TWAP_ORDER_SEED, vault.as_ref(), &order_id.to_le_bytes()],
+        &crate::ID,
+    )
+    .0
+}
+
+pub fn user_nonce_address(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[USER_NONCE_SEED, user.as_ref()], &crate::ID).0
+}
+
+pub fn delegation_terms_address(
+    vault: &Pubkey,
+    user: &Pubkey,
+    input_mint: &Pubkey,
+    output_mint: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            DELEGATION_TERMS_SEED,
+            vault.as_ref(),
+            user.as_ref(),
+            input_mint.as_ref(),
+            output_mint.as_ref(),
+        ],
+        &crate::ID,
+    )
+    .0
+}
+
+pub fn event_authority_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &crate::ID).0
+}
+
+/// Maps the account list of a Jupiter swap instruction onto the remaining
+/// accounts of a vault swap. The vault signs for itself inside the program, so
+/// every signer flag is cleared.
+pub fn jupiter_account_metas(accounts: &[AccountMeta]) -> Vec<AccountMeta> {
+    accounts
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: account.pubkey,
+            is_signer: false,
+            is_writable: account.is_writable,
+        })
+        .collect()
+}
+
+/// On-chain counterpart of [`jupiter_account_metas`].
+pub fn jupiter_remaining_accounts<'info>(
+    accounts: &[AccountInfo<'info>],
+) -> Vec<AccountInfo<'info>> {
+    accounts
+        .iter()
+        .map(|account| {
+            let mut account = account.clone();
+            account.is_signer = false;
+            account
+        })
+        .collect()
+}
+
+pub fn swap<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, crate::cpi::accounts::Swap<'info>>,
+    jupiter_accounts: &[AccountInfo<'info>],
+    data: Vec<u8>,
+) -> Result<SwapResult> {
+    let ctx = ctx.with_remaining_accounts(jupiter_remaining_accounts(jupiter_accounts));
+    Ok(crate::cpi::swap(ctx, data)?.get())
+}
+
+pub fn swap_exact_out<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, crate::cpi::accounts::Swap<'info>>,
+    jupiter_accounts: &[AccountInfo<'info>],
+    data: Vec<u8>,
+    out_amount: u64,
+    max_in: u64,
+) -> Result<SwapExactOutResult> {
+    let ctx = ctx.with_remaining_accounts(jupiter_remaining_accounts(jupiter_accounts));
+    Ok(crate::cpi::swap_exact_out(ctx, data, out_amount, max_in)?.get())
+}
+
+/// Unlike the vault swaps, signer flags on `jupiter_accounts` are forwarded
+/// as-is because the user signs for their own transfer.
+pub fn swap_from_user<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, crate::cpi::accounts::SwapFromUser<'info>>,
+    jupiter_accounts: &[AccountInfo<'info>],
+    data: Vec<u8>,
+    min_amount_out: u64,
+) -> Result<SwapResult> {
+    let ctx = ctx.with_remaining_accounts(jupiter_accounts.to_vec());
+    Ok(crate::cpi::swap_from_user(ctx, data, min_amount_out)?.get())
+}
+
+/// Swaps out of a user token account the vault is delegate of, within the
+/// user's `DelegationTerms`.
+pub fn swap_delegated<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, crate::cpi::accounts::SwapDelegated<'info>>,
+    jupiter_accounts: &[AccountInfo<'info>],
+    data: Vec<u8>,
+    min_amount_out: u64,
+) -> Result<SwapResult> {
+    let ctx = ctx.with_remaining_accounts(jupiter_remaining_accounts(jupiter_accounts));
+    Ok(crate::cpi::swap_delegated(ctx, data, min_amount_out)?.get())
+}
+
+pub fn execute_twap_slice<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, crate::cpi::accounts::ExecuteTwapSlice<'info>>,
+    jupiter_accounts: &[AccountInfo<'info>],
+    data: Vec<u8>,
+) -> Result<SwapResult> {
+    let ctx = ctx.with_remaining_accounts(jupiter_remaining_accounts(jupiter_accounts));
+    Ok(crate::cpi::execute_twap_slice(ctx, data)?.get())
+}
+
+/// Like [`swap`], with `hook_accounts` appended after the Jupiter accounts
+/// for the post-swap hook.
+pub fn swap_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, crate::cpi::accounts::Swap<'info>>,
+    jupiter_accounts: &[AccountInfo<'info>],
+    hook_accounts: &[AccountInfo<'info>],
+    data: Vec<u8>,
+    hook_data: Vec<u8>,
+) -> Result<SwapResult> {
+    let hook_account_count = u8::try_from(hook_accounts.len())
+        .map_err(|_| error!(crate::errors::CpiSwapError::MathOverflow))?;
+    let mut remaining_accounts = jupiter_remaining_accounts(jupiter_accounts);
+    remaining_accounts.extend(jupiter_remaining_accounts(hook_accounts));
+    let ctx = ctx.with_remaining_accounts(remaining_accounts);
+    Ok(crate::cpi::swap_with_hook(ctx, data, hook_data, hook_account_count)?.get())
+}
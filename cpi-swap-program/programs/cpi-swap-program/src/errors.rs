@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum CpiSwapError {
+    #[msg("Signer is not the config admin")]
+    Unauthorized,
+    #[msg("Signer is not a manager of the strategy")]
+    UnauthorizedManager,
+    #[msg("Too many managers or keepers")]
+    TooManyRoleMembers,
+    #[msg("Volume window must be positive")]
+    InvalidVolumeWindow,
+    #[msg("Vault volume limit exceeded for the current window")]
+    VaultVolumeLimitExceeded,
+    #[msg("Operator volume limit exceeded for the current window")]
+    OperatorVolumeLimitExceeded,
+    #[msg("Too many aggregators in the registry")]
+    TooManyAggregators,
+    #[msg("Too many instruction discriminators for one aggregator")]
+    TooManyDiscriminators,
+    #[msg("Aggregator program is not in the registry")]
+    AggregatorNotAllowed,
+    #[msg("Aggregator instruction is not allowed by the registry")]
+    InstructionNotAllowed,
+    #[msg("Too many venues in the route policy")]
+    TooManyVenues,
+    #[msg("Route plan could not be decoded")]
+    UndecodedRoute,
+    #[msg("Route plan has too many hops")]
+    TooManyHops,
+    #[msg("Route plan uses a venue not allowed by the route policy")]
+    VenueNotAllowed,
+    #[msg("Route slippage exceeds the route policy")]
+    SlippageTooHigh,
+    #[msg("Route writes to a vault-controlled token account other than the declared ones")]
+    UnexpectedVaultTokenAccount,
+    #[msg("Vault lamport balance changed during the swap")]
+    VaultLamportsChanged,
+    #[msg("Platform fee account is not owned by the referral authority")]
+    InvalidPlatformFeeAccount,
+    #[msg("Route platform fee does not match the configured fee")]
+    PlatformFeeMismatch,
+    #[msg("Route transfer authority is not the signing user")]
+    InvalidTransferAuthority,
+    #[msg("Swap output is below the minimum amount")]
+    MinimumOutputNotMet,
+    #[msg("Vault is not the delegate of the user token account")]
+    VaultNotDelegate,
+    #[msg("Route does not deliver its output to the expected token account")]
+    InvalidRouteDestination,
+    #[msg("Delegated swap price is below the user's minimum")]
+    DelegatedPriceTooLow,
+    #[msg("Expected an ed25519 signature instruction before this one")]
+    InvalidSignatureInstruction,
+    #[msg("Intent is not signed by the user")]
+    InvalidIntentSignature,
+    #[msg("Intent does not match the accounts or the realized swap")]
+    IntentMismatch,
+    #[msg("Intent has expired")]
+    IntentExpired,
+    #[msg("Intent nonce has already been used or is out of order")]
+    InvalidIntentNonce,
+    #[msg("Too many hook programs")]
+    TooManyHooks,
+    #[msg("Hook program is not in the hook registry")]
+    HookNotAllowed,
+    #[msg("Hook program, hook registry or hook accounts were not passed")]
+    MissingHookAccounts,
+    #[msg("A hook cannot be combined with a recipient token account")]
+    HookWithRecipient,
+    #[msg("Hook spent more than the swap output")]
+    HookSpendExceeded,
+    #[msg("Sweep route is missing its token account or route accounts")]
+    MissingSweepAccounts,
+    #[msg("Sweep source must be a vault token account of another mint")]
+    InvalidSweepAccount,
+    #[msg("Token account still holds a balance")]
+    TokenAccountNotEmpty,
+    #[msg("Too many allowed mints")]
+    TooManyMints,
+    #[msg("Mint is not allowed by the strategy")]
+    MintNotAllowed,
+    #[msg("Too many recipients")]
+    TooManyRecipients,
+    #[msg("Recipient is not allowed by the strategy")]
+    RecipientNotAllowed,
+    #[msg("Keeper reward exceeds the strategy's per-fill or per-order cap")]
+    KeeperRewardTooHigh,
+    #[msg("Only managers may close an order before it is filled or expires")]
+    OrderStillOpen,
+    #[msg("Fill did not swap any input or output")]
+    EmptyFill,
+    #[msg("Fill spent more than is left of the order's open tranche")]
+    FillExceedsTranche,
+    #[msg("Signer is not a keeper of the strategy")]
+    UnauthorizedKeeper,
+    #[msg("Invalid TWAP order parameters")]
+    InvalidTwapOrder,
+    #[msg("Next TWAP slice is not open yet")]
+    TwapSliceNotReady,
+    #[msg("TWAP order window has ended")]
+    TwapOrderExpired,
+    #[msg("TWAP order has no slices left")]
+    TwapOrderFilled,
+    #[msg("TWAP slice price is below the order minimum or too far from the average fill price")]
+    TwapPriceDeviation,
+    #[msg("Vault fee exceeds 10000 basis points")]
+    InvalidVaultFee,
+    #[msg("Too many NAV assets")]
+    TooManyNavAssets,
+    #[msg("Missing NAV token or oracle accounts")]
+    MissingNavAccounts,
+    #[msg("NAV token account does not match the registered asset")]
+    InvalidNavAccount,
+    #[msg("Oracle account is not a verified price update")]
+    InvalidOracleAccount,
+    #[msg("Oracle price is too old")]
+    StaleOraclePrice,
+    #[msg("Oracle price is not positive")]
+    InvalidOraclePrice,
+    #[msg("Oracle account is for a different price feed")]
+    InvalidOracleFeed,
+    #[msg("Oracle price confidence interval is too wide")]
+    OraclePriceUncertain,
+    #[msg("Input mint has a volume limit, so the operator volume account is required")]
+    OperatorVolumeRequired,
+    #[msg("Burning or harvesting withheld transfer fees needs the mint passed as writable")]
+    MintNotWritable,
+    #[msg("Vault fees must be accrued against a NAV computed at the same time")]
+    StaleNav,
+    #[msg("Too many writable accounts for one hook")]
+    TooManyHookAccounts,
+    #[msg("Hook writes to an account that is not pinned for it in the hook registry")]
+    HookAccountNotPinned,
+    #[msg("Instruction data is not a supported Jupiter route")]
+    InvalidRouteInstruction,
+    #[msg("Vault received less than the requested output amount")]
+    OutputAmountNotReceived,
+    #[msg("Swap spent more than the maximum input amount")]
+    MaxInputExceeded,
+    #[msg("Math overflow")]
+    MathOverflow,
+}
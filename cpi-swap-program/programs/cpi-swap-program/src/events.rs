@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+
+use crate::CloseRemainder;
+
+#[event]
+pub struct SwapEvent {
+    pub operator: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+#[event]
+pub struct SwapExactOutEvent {
+    pub operator: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub max_in: u64,
+    pub unused_input: u64,
+}
+
+#[event]
+pub struct UserSwapEvent {
+    pub user: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+#[event]
+pub struct DelegatedSwapEvent {
+    pub operator: Pubkey,
+    pub user: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub remaining_allowance: u64,
+}
+
+#[event]
+pub struct IntentSwapEvent {
+    pub relayer: Pubkey,
+    pub user: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct HookInvokedEvent {
+    pub hook_program: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SweepEvent {
+    pub output_mint: Pubkey,
+    pub swept: u8,
+    pub skipped: u8,
+    pub closed: u8,
+    pub amount_out: u64,
+}
+
+#[event]
+pub struct TokenAccountClosedEvent {
+    pub token_account: Pubkey,
+    pub mint: Pubkey,
+    pub remainder: CloseRemainder,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub rent: u64,
+}
+
+#[event]
+pub struct WithdrawEvent {
+    pub strategy_id: u64,
+    pub mint: Pubkey,
+    pub destination_token_account: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct LegacyVaultMigratedEvent {
+    pub strategy_id: u64,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct FeeReimbursedEvent {
+    pub operator: Pubkey,
+    pub vault: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct TwapSliceFilledEvent {
+    pub order: Pubkey,
+    pub keeper: Pubkey,
+    pub slice: u16,
+    pub slice_filled: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub filled_in: u64,
+    pub filled_out: u64,
+    pub average_price: u64,
+    pub keeper_reward: u64,
+}
+
+#[event]
+pub struct TwapOrderClosedEvent {
+    pub order: Pubkey,
+    pub filled_slices: u16,
+    pub filled_in: u64,
+    pub filled_out: u64,
+}
+
+#[event]
+pub struct VaultFeesAccruedEvent {
+    pub vault: Pubkey,
+    pub nav: u64,
+    pub management_fee: u64,
+    pub performance_fee: u64,
+    pub shares: u64,
+    pub high_water_mark: u128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NavComputedEvent {
+    pub vault: Pubkey,
+    pub nav: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OutputSentEvent {
+    pub recipient_token_account: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
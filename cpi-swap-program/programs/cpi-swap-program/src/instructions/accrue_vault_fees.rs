@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::{CONFIG_SEED, STRATEGY_SEED, VAULT_FEES_SEED, VAULT_NAV_SEED, VAULT_SEED},
+    errors::CpiSwapError,
+    events::VaultFeesAccruedEvent,
+    state::{StrategyConfig, VaultConfig, VaultFees, VaultNav},
+};
+
+/// Accrues the vault's management and performance fees against its NAV and
+/// mints them as shares to the fee recipient. The NAV must have been computed
+/// at the same time, so run `compute_nav` right before this in the same
+/// transaction. Only the admin and keepers may accrue, since the accrual
+/// locks in a performance fee at whatever prices the oracles report.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AccrueVaultFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+      constraint = authority.key() == config.admin
+        || strategy.is_keeper(&authority.key()) @ CpiSwapError::Unauthorized,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      seeds = [VAULT_FEES_SEED, vault.key().as_ref()],
+      bump = vault_fees.bump,
+      has_one = share_mint,
+    )]
+    pub vault_fees: Account<'info, VaultFees>,
+
+    #[account(
+      seeds = [VAULT_NAV_SEED, vault.key().as_ref()],
+      bump = vault_nav.bump,
+    )]
+    pub vault_nav: Account<'info, VaultNav>,
+
+    #[account(mut)]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      token::mint = share_mint,
+      token::authority = vault_fees.fee_recipient,
+      token::token_program = token_program,
+    )]
+    pub fee_recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+pub fn handle_accrue_vault_fees(ctx: Context<AccrueVaultFees>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let vault_nav = &ctx.accounts.vault_nav;
+    require_eq!(vault_nav.updated_at, now, CpiSwapError::StaleNav);
+    let nav = vault_nav.nav;
+
+    let vault_fees = &mut ctx.accounts.vault_fees;
+    if vault_nav.assets_set_at >= vault_fees.accrued_at {
+        vault_fees.restart();
+    }
+    let accrual = vault_fees.accrue(nav, ctx.accounts.share_mint.supply, now)?;
+
+    if accrual.shares > 0 {
+        let accounts = &ctx.accounts;
+        let strategy_id = accounts.strategy.strategy_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] =
+            &[&[VAULT_SEED, &strategy_id, &[accounts.strategy.vault_bump]]];
+        mint_to(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: accounts.share_mint.to_account_info(),
+                    to: accounts.fee_recipient_token_account.to_account_info(),
+                    authority: accounts.vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            accrual.shares,
+        )?;
+    }
+
+    emit_cpi!(VaultFeesAccruedEvent {
+        vault: ctx.accounts.vault.key(),
+        nav,
+        management_fee: accrual.management_fee,
+        performance_fee: accrual.performance_fee,
+        shares: accrual.shares,
+        high_water_mark: ctx.accounts.vault_fees.high_water_mark,
+        timestamp: now,
+    });
+
+    Ok(())
+}
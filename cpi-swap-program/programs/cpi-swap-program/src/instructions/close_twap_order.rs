@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{STRATEGY_SEED, TWAP_ORDER_SEED, VAULT_SEED},
+    events::TwapOrderClosedEvent,
+    state::{CrankableOrder, StrategyConfig, TwapOrder},
+};
+
+/// Lets a manager cancel a TWAP order at any time, and anyone clean one up
+/// once it is filled or its window has ended. The rent goes back to the
+/// manager that created it.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseTwapOrder<'info> {
+    pub closer: Signer<'info>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      close = creator,
+      seeds = [TWAP_ORDER_SEED, vault.key().as_ref(), &twap_order.order_id.to_le_bytes()],
+      bump = twap_order.bump,
+      has_one = creator,
+    )]
+    pub twap_order: Account<'info, TwapOrder>,
+
+    #[account(mut)]
+    pub creator: SystemAccount<'info>,
+}
+
+pub fn handle_close_twap_order(ctx: Context<CloseTwapOrder>) -> Result<()> {
+    let twap_order = &ctx.accounts.twap_order;
+    twap_order.check_close(
+        &ctx.accounts.strategy,
+        ctx.accounts.closer.key,
+        Clock::get()?.unix_timestamp,
+    )?;
+    emit_cpi!(TwapOrderClosedEvent {
+        order: twap_order.key(),
+        filled_slices: twap_order.filled_slices,
+        filled_in: twap_order.fills.filled_in,
+        filled_out: twap_order.fills.filled_out,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022_extensions::{harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint},
+    token_interface::{
+        burn, close_account, transfer_checked, Burn, CloseAccount, Mint, TokenAccount,
+        TokenInterface, TransferChecked,
+    },
+};
+
+use crate::{
+    constants::{CONFIG_SEED, STRATEGY_SEED, VAULT_SEED},
+    errors::CpiSwapError,
+    events::TokenAccountClosedEvent,
+    state::{StrategyConfig, VaultConfig},
+    utils::withheld_transfer_fees,
+};
+
+/// What to do with a non-zero balance before closing a vault token account.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloseRemainder {
+    /// Fail unless the account is already empty.
+    Reject,
+    /// Move the balance to `withdraw_token_account`.
+    Withdraw,
+    /// Burn the balance. The mint must be passed as writable.
+    Burn,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseVaultTokenAccount<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Read-only unless the balance is burned or Token-2022 transfer fees
+    /// withheld in the account are harvested to it, which need it writable.
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      token::mint = mint,
+      token::authority = vault,
+      token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the balance when closing with [`CloseRemainder::Withdraw`].
+    #[account(
+      mut,
+      token::mint = mint,
+      token::token_program = token_program,
+    )]
+    pub withdraw_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: only receives the rent lamports
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+}
+
+pub fn handle_close_vault_token_account(
+    ctx: Context<CloseVaultTokenAccount>,
+    remainder: CloseRemainder,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let strategy_id = accounts.strategy.strategy_id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &strategy_id, &[accounts.strategy.vault_bump]]];
+    let amount = accounts.vault_token_account.amount;
+    let mint_is_writable = accounts.mint.to_account_info().is_writable;
+
+    // Token-2022 refuses to close an account that still holds withheld fees
+    if withheld_transfer_fees(&accounts.vault_token_account.to_account_info()) > 0 {
+        require!(mint_is_writable, CpiSwapError::MintNotWritable);
+        harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                accounts.token_program.to_account_info(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: accounts.token_program.to_account_info(),
+                    mint: accounts.mint.to_account_info(),
+                },
+            ),
+            vec![accounts.vault_token_account.to_account_info()],
+        )?;
+    }
+
+    if amount > 0 {
+        match remainder {
+            CloseRemainder::Reject => return err!(CpiSwapError::TokenAccountNotEmpty),
+            CloseRemainder::Withdraw => {
+                let withdraw_token_account = accounts
+                    .withdraw_token_account
+                    .as_ref()
+                    .ok_or(CpiSwapError::TokenAccountNotEmpty)?;
+                transfer_checked(
+                    CpiContext::new_with_signer(
+                        accounts.token_program.to_account_info(),
+                        TransferChecked {
+                            from: accounts.vault_token_account.to_account_info(),
+                            mint: accounts.mint.to_account_info(),
+                            to: withdraw_token_account.to_account_info(),
+                            authority: accounts.vault.to_account_info(),
+                        },
+                        signer_seeds,
+                    ),
+                    amount,
+                    accounts.mint.decimals,
+                )?;
+            }
+            CloseRemainder::Burn => {
+                require!(mint_is_writable, CpiSwapError::MintNotWritable);
+                burn(
+                    CpiContext::new_with_signer(
+                        accounts.token_program.to_account_info(),
+                        Burn {
+                            mint: accounts.mint.to_account_info(),
+                            from: accounts.vault_token_account.to_account_info(),
+                            authority: accounts.vault.to_account_info(),
+                        },
+                        signer_seeds,
+                    ),
+                    amount,
+                )?;
+            }
+        }
+    }
+
+    let rent = accounts.vault_token_account.to_account_info().lamports();
+    close_account(CpiContext::new_with_signer(
+        accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: accounts.vault_token_account.to_account_info(),
+            destination: accounts.destination.to_account_info(),
+            authority: accounts.vault.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    emit_cpi!(TokenAccountClosedEvent {
+        token_account: accounts.vault_token_account.key(),
+        mint: accounts.mint.key(),
+        remainder,
+        amount,
+        destination: accounts.destination.key(),
+        rent,
+    });
+
+    Ok(())
+}
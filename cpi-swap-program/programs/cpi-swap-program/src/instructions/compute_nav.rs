@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{NAV_DECIMALS, STRATEGY_SEED, VAULT_NAV_SEED, VAULT_SEED},
+    errors::CpiSwapError,
+    events::NavComputedEvent,
+    oracle::OraclePrice,
+    state::{StrategyConfig, VaultNav},
+    utils::unpack_token_account,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NavResult {
+    pub nav: u64,
+    pub timestamp: i64,
+}
+
+/// Values the vault's registered token accounts at their oracle prices and
+/// stores the sum. Anyone may call it; fees are only accrued against the sum
+/// by `accrue_vault_fees`. Each asset is laid out in the remaining accounts
+/// as its token account followed by its oracle account.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ComputeNav<'info> {
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      seeds = [VAULT_NAV_SEED, vault.key().as_ref()],
+      bump = vault_nav.bump,
+    )]
+    pub vault_nav: Account<'info, VaultNav>,
+}
+
+pub fn handle_compute_nav(ctx: Context<ComputeNav>) -> Result<NavResult> {
+    let now = Clock::get()?.unix_timestamp;
+    let vault = ctx.accounts.vault.key();
+    let vault_nav = &mut ctx.accounts.vault_nav;
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        vault_nav.assets.len() * 2,
+        CpiSwapError::MissingNavAccounts
+    );
+
+    let mut nav: u64 = 0;
+    for (asset, accounts) in vault_nav
+        .assets
+        .iter()
+        .zip(ctx.remaining_accounts.chunks_exact(2))
+    {
+        let (token_account, oracle) = (&accounts[0], &accounts[1]);
+        require_keys_eq!(
+            *token_account.key,
+            asset.token_account,
+            CpiSwapError::InvalidNavAccount
+        );
+        require_keys_eq!(*oracle.key, asset.oracle, CpiSwapError::InvalidNavAccount);
+
+        // A closed token account holds nothing
+        let amount = match unpack_token_account(token_account) {
+            Some(token_account) => {
+                require!(
+                    token_account.owner == vault && token_account.mint == asset.mint,
+                    CpiSwapError::InvalidNavAccount
+                );
+                token_account.amount
+            }
+            None if token_account.data_is_empty() => 0,
+            None => return err!(CpiSwapError::InvalidNavAccount),
+        };
+        if amount == 0 {
+            continue;
+        }
+
+        let price = OraclePrice::from_pyth(oracle, &asset.feed_id)?;
+        require_gte!(
+            price
+                .publish_time
+                .saturating_add_unsigned(vault_nav.max_price_age),
+            now,
+            CpiSwapError::StaleOraclePrice
+        );
+        price.check_confidence(vault_nav.max_confidence_bps)?;
+        let value = price.value(amount, asset.decimals, NAV_DECIMALS)?;
+        nav = nav.checked_add(value).ok_or(CpiSwapError::MathOverflow)?;
+    }
+
+    vault_nav.nav = nav;
+    vault_nav.updated_at = now;
+
+    emit_cpi!(NavComputedEvent {
+        vault,
+        nav,
+        timestamp: now,
+    });
+
+    Ok(NavResult {
+        nav,
+        timestamp: now,
+    })
+}
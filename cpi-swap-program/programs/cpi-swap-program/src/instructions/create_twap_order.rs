@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    constants::{STRATEGY_SEED, TWAP_ORDER_SEED, VAULT_SEED},
+    errors::CpiSwapError,
+    state::{StrategyConfig, TwapOrder},
+};
+
+/// Terms of a new [`TwapOrder`]; see its fields.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TwapOrderParams {
+    pub total_in: u64,
+    pub slices: u16,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub min_price: u64,
+    pub max_deviation_bps: u16,
+    pub keeper_reward: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct CreateTwapOrder<'info> {
+    #[account(mut)]
+    pub manager: Signer<'info>,
+
+    #[account(constraint = strategy.allows_mint(&input_mint.key()) @ CpiSwapError::MintNotAllowed)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+    #[account(constraint = strategy.allows_mint(&output_mint.key()) @ CpiSwapError::MintNotAllowed)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+      constraint = strategy.is_manager(&manager.key()) @ CpiSwapError::UnauthorizedManager,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      init,
+      payer = manager,
+      space = 8 + TwapOrder::INIT_SPACE,
+      seeds = [TWAP_ORDER_SEED, vault.key().as_ref(), &order_id.to_le_bytes()],
+      bump
+    )]
+    pub twap_order: Account<'info, TwapOrder>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_create_twap_order(
+    ctx: Context<CreateTwapOrder>,
+    order_id: u64,
+    params: TwapOrderParams,
+) -> Result<()> {
+    require_keys_neq!(
+        ctx.accounts.input_mint.key(),
+        ctx.accounts.output_mint.key(),
+        CpiSwapError::InvalidTwapOrder
+    );
+    require!(
+        params.slices > 0 && params.total_in >= u64::from(params.slices) && params.min_price > 0,
+        CpiSwapError::InvalidTwapOrder
+    );
+    // Every slice needs an interval of at least one second
+    let duration = params
+        .end_time
+        .checked_sub(params.start_time)
+        .ok_or(CpiSwapError::MathOverflow)?;
+    require_gte!(
+        duration,
+        i64::from(params.slices),
+        CpiSwapError::InvalidTwapOrder
+    );
+    ctx.accounts
+        .strategy
+        .check_keeper_reward(params.keeper_reward, params.slices)?;
+
+    let twap_order = &mut ctx.accounts.twap_order;
+    twap_order.vault = ctx.accounts.vault.key();
+    twap_order.order_id = order_id;
+    twap_order.creator = ctx.accounts.manager.key();
+    twap_order.input_mint = ctx.accounts.input_mint.key();
+    twap_order.output_mint = ctx.accounts.output_mint.key();
+    twap_order.total_in = params.total_in;
+    twap_order.slices = params.slices;
+    twap_order.start_time = params.start_time;
+    twap_order.end_time = params.end_time;
+    twap_order.min_price = params.min_price;
+    twap_order.max_deviation_bps = params.max_deviation_bps;
+    twap_order.keeper_reward = params.keeper_reward;
+    twap_order.bump = ctx.bumps.twap_order;
+
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    aggregator::{pay_from_vault, settle_vault_route, spare_vault_lamports, VaultRoute},
+    constants::{
+        AGGREGATOR_REGISTRY_SEED, ROUTE_POLICY_SEED, STRATEGY_SEED, TWAP_ORDER_SEED, VAULT_SEED,
+        VAULT_STATS_SEED, VOLUME_LIMIT_SEED,
+    },
+    errors::CpiSwapError,
+    events::TwapSliceFilledEvent,
+    state::{AggregatorRegistry, RoutePolicy, StrategyConfig, TwapOrder, VaultStats, VolumeLimit},
+    SwapResult,
+};
+
+/// Fills the open slice of a TWAP order out of the vault, in full or in
+/// part. Any keeper of the strategy may crank it; the order fixes the mints,
+/// slice size and price band, so the keeper only picks the route and how
+/// much of the slice it can fill. Slices pay no platform fee; the keeper is
+/// paid its share of the order's `keeper_reward`, within the strategy's caps,
+/// from the vault's spare SOL.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteTwapSlice<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(constraint = strategy.allows_mint(&input_mint.key()) @ CpiSwapError::MintNotAllowed)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+    pub input_mint_program: Interface<'info, TokenInterface>,
+    #[account(constraint = strategy.allows_mint(&output_mint.key()) @ CpiSwapError::MintNotAllowed)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+    pub output_mint_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+      constraint = strategy.is_keeper(&keeper.key()) @ CpiSwapError::UnauthorizedKeeper,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      mut,
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      seeds = [TWAP_ORDER_SEED, vault.key().as_ref(), &twap_order.order_id.to_le_bytes()],
+      bump = twap_order.bump,
+      has_one = input_mint @ CpiSwapError::InvalidTwapOrder,
+      has_one = output_mint @ CpiSwapError::InvalidTwapOrder,
+    )]
+    pub twap_order: Account<'info, TwapOrder>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=input_mint_program,
+    )]
+    pub vault_input_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+      mut,
+      associated_token::mint=output_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=output_mint_program,
+    )]
+    pub vault_output_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: the vault's `VolumeLimit` for the input mint, if the admin set
+    /// one. Unset mints have no account here and are not capped.
+    #[account(
+      mut,
+      seeds = [VOLUME_LIMIT_SEED, vault.key().as_ref(), input_mint.key().as_ref()],
+      bump,
+    )]
+    pub input_volume_limit: UncheckedAccount<'info>,
+
+    #[account(
+      init_if_needed,
+      payer = keeper,
+      space = 8 + VaultStats::INIT_SPACE,
+      seeds = [VAULT_STATS_SEED, vault.key().as_ref()],
+      bump
+    )]
+    pub vault_stats: Account<'info, VaultStats>,
+
+    #[account(
+      seeds = [AGGREGATOR_REGISTRY_SEED],
+      bump = aggregator_registry.bump,
+    )]
+    pub aggregator_registry: Account<'info, AggregatorRegistry>,
+
+    #[account(
+      seeds = [ROUTE_POLICY_SEED, vault.key().as_ref()],
+      bump = route_policy.bump,
+    )]
+    pub route_policy: Account<'info, RoutePolicy>,
+
+    /// CHECK: checked against `aggregator_registry` before invoking
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ExecuteTwapSlice<'info> {
+    pub fn invoke_jupiter(&self, remaining_accounts: &[AccountInfo], data: Vec<u8>) -> Result<u64> {
+        VaultRoute {
+            aggregator_registry: &self.aggregator_registry,
+            route_policy: &self.route_policy,
+            strategy: &self.strategy,
+            aggregator_program: self.aggregator_program.key(),
+            vault: self.vault.to_account_info(),
+            writable: &[
+                self.vault_input_token_account.key(),
+                self.vault_output_token_account.key(),
+            ],
+            destination: self.vault_output_token_account.key(),
+            platform_fee: None,
+        }
+        .invoke(remaining_accounts, data)
+    }
+
+    /// Pays the keeper the reward for filling `amount_in` of a slice of
+    /// `slice_in` within the strategy's caps, or whatever SOL the vault has
+    /// above rent if that is less, and returns the lamports paid.
+    pub fn pay_keeper(&mut self, amount_in: u64, slice_in: u64) -> Result<u64> {
+        let spare = spare_vault_lamports(&self.vault)?;
+        let lamports = self
+            .twap_order
+            .keeper_reward_due(&self.strategy, amount_in, slice_in)
+            .min(spare);
+        if lamports == 0 {
+            return Ok(0);
+        }
+
+        pay_from_vault(
+            &self.system_program,
+            &self.vault,
+            &self.strategy,
+            self.keeper.to_account_info(),
+            lamports,
+        )?;
+
+        let twap_order = &mut self.twap_order;
+        twap_order.rewards_paid = twap_order
+            .rewards_paid
+            .checked_add(lamports)
+            .ok_or(CpiSwapError::MathOverflow)?;
+        Ok(lamports)
+    }
+
+    /// Counts the slice against the vault's input volume limit. Keepers have
+    /// no per-operator allowance.
+    pub fn record_volume(&mut self, amount_in: u64, now: i64) -> Result<()> {
+        VolumeLimit::record_at(&self.input_volume_limit, now, amount_in)?;
+        Ok(())
+    }
+}
+
+pub fn handle_execute_twap_slice(
+    ctx: Context<ExecuteTwapSlice>,
+    data: Vec<u8>,
+) -> Result<SwapResult> {
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    let twap_order = &ctx.accounts.twap_order;
+    // The fill itself is bounded by the rest of the slice in `record_fill`
+    twap_order.next_slice(now)?;
+    let slice = twap_order.filled_slices;
+    let slice_in = twap_order.slice_in();
+
+    let input_before = ctx.accounts.vault_input_token_account.amount;
+    let output_before = ctx.accounts.vault_output_token_account.amount;
+
+    let min_amount_out = ctx.accounts.invoke_jupiter(ctx.remaining_accounts, data)?;
+
+    let (amount_in, amount_out) = settle_vault_route(
+        &mut ctx.accounts.vault_input_token_account,
+        &mut ctx.accounts.vault_output_token_account,
+        input_before,
+        output_before,
+        min_amount_out,
+    )?;
+
+    let slice_filled = ctx
+        .accounts
+        .twap_order
+        .record_fill(amount_in, amount_out, now)?;
+    ctx.accounts.record_volume(amount_in, now)?;
+    let vault = ctx.accounts.vault.key();
+    let vault_stats = &mut ctx.accounts.vault_stats;
+    vault_stats.init_if_needed(vault, ctx.bumps.vault_stats);
+    vault_stats.record_swap(
+        ctx.accounts.input_mint.key(),
+        ctx.accounts.output_mint.key(),
+        amount_in,
+        amount_out,
+        clock.slot,
+    )?;
+    let keeper_reward = ctx.accounts.pay_keeper(amount_in, slice_in)?;
+
+    let twap_order = &ctx.accounts.twap_order;
+    emit_cpi!(TwapSliceFilledEvent {
+        order: twap_order.key(),
+        keeper: ctx.accounts.keeper.key(),
+        slice,
+        slice_filled,
+        amount_in,
+        amount_out,
+        filled_in: twap_order.fills.filled_in,
+        filled_out: twap_order.fills.filled_out,
+        average_price: twap_order.fills.average_price(),
+        keeper_reward,
+    });
+
+    Ok(SwapResult {
+        amount_in,
+        amount_out,
+        input_mint: ctx.accounts.input_mint.key(),
+        output_mint: ctx.accounts.output_mint.key(),
+    })
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{STRATEGY_SEED, VAULT_SEED, VAULT_STATS_SEED},
+    state::{StrategyConfig, VaultStats},
+};
+
+/// Read-only view that returns the vault's [`VaultStats`] as return data, for
+/// simulation by dashboards and for CPI callers.
+#[derive(Accounts)]
+pub struct GetVaultState<'info> {
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      seeds = [VAULT_STATS_SEED, vault.key().as_ref()],
+      bump = vault_stats.bump,
+    )]
+    pub vault_stats: Account<'info, VaultStats>,
+}
+
+pub fn handle_get_vault_state(ctx: Context<GetVaultState>) -> Result<VaultStats> {
+    Ok(ctx.accounts.vault_stats.clone().into_inner())
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::CONFIG_SEED, program::CpiSwapProgram, state::VaultConfig};
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+      init,
+      payer = admin,
+      space = 8 + VaultConfig::INIT_SPACE,
+      seeds = [CONFIG_SEED],
+      bump
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, CpiSwapProgram>,
+
+    /// Only the upgrade authority can claim the admin role.
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()))]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+    ctx.accounts.config.set_inner(VaultConfig {
+        admin: ctx.accounts.admin.key(),
+        referral_authority: Pubkey::default(),
+        bump: ctx.bumps.config,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::{CONFIG_SEED, STRATEGY_SEED, VAULT_FEES_SEED, VAULT_SEED},
+    errors::CpiSwapError,
+    state::{StrategyConfig, VaultConfig, VaultFees},
+};
+
+/// Mints vault shares for a deposit. The admin prices the issue at the
+/// current NAV per share and should accrue fees first, so the deposit does
+/// not pay for time it was not in the vault.
+#[derive(Accounts)]
+pub struct IssueVaultShares<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      seeds = [VAULT_FEES_SEED, vault.key().as_ref()],
+      bump = vault_fees.bump,
+      has_one = share_mint,
+    )]
+    pub vault_fees: Account<'info, VaultFees>,
+
+    #[account(mut)]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      token::mint = share_mint,
+      token::token_program = token_program,
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+pub fn handle_issue_vault_shares(ctx: Context<IssueVaultShares>, amount: u64) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let strategy_id = accounts.strategy.strategy_id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &strategy_id, &[accounts.strategy.vault_bump]]];
+
+    mint_to(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            MintTo {
+                mint: accounts.share_mint.to_account_info(),
+                to: accounts.recipient_token_account.to_account_info(),
+                authority: accounts.vault.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
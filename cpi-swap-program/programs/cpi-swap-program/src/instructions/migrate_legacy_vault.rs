@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    constants::{CONFIG_SEED, STRATEGY_SEED, VAULT_SEED},
+    errors::CpiSwapError,
+    events::LegacyVaultMigratedEvent,
+    state::{StrategyConfig, VaultConfig},
+};
+
+/// Moves one mint's balance from the vault PDA used before vaults were scoped
+/// to strategies, derived from `[VAULT_SEED]` alone, into a strategy vault.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateLegacyVault<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      seeds = [VAULT_SEED],
+      bump,
+    )]
+    pub legacy_vault: SystemAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      token::mint = mint,
+      token::authority = legacy_vault,
+      token::token_program = token_program,
+    )]
+    pub legacy_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+      mut,
+      token::mint = mint,
+      token::authority = vault,
+      token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+pub fn handle_migrate_legacy_vault(ctx: Context<MigrateLegacyVault>) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let amount = accounts.legacy_vault_token_account.amount;
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.legacy_vault]]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: accounts.legacy_vault_token_account.to_account_info(),
+                mint: accounts.mint.to_account_info(),
+                to: accounts.vault_token_account.to_account_info(),
+                authority: accounts.legacy_vault.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        accounts.mint.decimals,
+    )?;
+
+    emit_cpi!(LegacyVaultMigratedEvent {
+        strategy_id: accounts.strategy.strategy_id,
+        mint: accounts.mint.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+pub mod accrue_vault_fees;
+pub mod close_twap_order;
+pub mod close_vault_token_account;
+pub mod compute_nav;
+pub mod create_twap_order;
+pub mod execute_twap_slice;
+pub mod get_vault_state;
+pub mod initialize_config;
+pub mod issue_vault_shares;
+pub mod migrate_legacy_vault;
+pub mod remove_aggregator;
+pub mod remove_hook;
+pub mod set_aggregator;
+pub mod set_delegation_terms;
+pub mod set_fee_reimbursement;
+pub mod set_hook;
+pub mod set_keeper_reward_limits;
+pub mod set_nav_assets;
+pub mod set_referral_authority;
+pub mod set_route_policy;
+pub mod set_strategy;
+pub mod set_strategy_roles;
+pub mod set_vault_fees;
+pub mod set_volume_limit;
+pub mod swap;
+pub mod swap_delegated;
+pub mod swap_exact_out;
+pub mod swap_from_user;
+pub mod swap_intent;
+pub mod swap_with_hook;
+pub mod sweep;
+pub mod withdraw;
+
+pub use accrue_vault_fees::*;
+pub use close_twap_order::*;
+pub use close_vault_token_account::*;
+pub use compute_nav::*;
+pub use create_twap_order::*;
+pub use execute_twap_slice::*;
+pub use get_vault_state::*;
+pub use initialize_config::*;
+pub use issue_vault_shares::*;
+pub use migrate_legacy_vault::*;
+pub use remove_aggregator::*;
+pub use remove_hook::*;
+pub use set_aggregator::*;
+pub use set_delegation_terms::*;
+pub use set_fee_reimbursement::*;
+pub use set_hook::*;
+pub use set_keeper_reward_limits::*;
+pub use set_nav_assets::*;
+pub use set_referral_authority::*;
+pub use set_route_policy::*;
+pub use set_strategy::*;
+pub use set_strategy_roles::*;
+pub use set_vault_fees::*;
+pub use set_volume_limit::*;
+pub use swap::*;
+pub use swap_delegated::*;
+pub use swap_exact_out::*;
+pub use swap_from_user::*;
+pub use swap_intent::*;
+pub use swap_with_hook::*;
+pub use sweep::*;
+pub use withdraw::*;
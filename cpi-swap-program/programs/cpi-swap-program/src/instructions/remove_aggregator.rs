@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{AGGREGATOR_REGISTRY_SEED, CONFIG_SEED},
+    errors::CpiSwapError,
+    state::{AggregatorRegistry, VaultConfig},
+};
+
+#[derive(Accounts)]
+pub struct RemoveAggregator<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      mut,
+      seeds = [AGGREGATOR_REGISTRY_SEED],
+      bump = aggregator_registry.bump,
+    )]
+    pub aggregator_registry: Account<'info, AggregatorRegistry>,
+}
+
+pub fn handle_remove_aggregator(ctx: Context<RemoveAggregator>, program_id: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.aggregator_registry;
+    let len_before = registry.aggregators.len();
+    registry
+        .aggregators
+        .retain(|entry| entry.program_id != program_id);
+    require_neq!(
+        len_before,
+        registry.aggregators.len(),
+        CpiSwapError::AggregatorNotAllowed
+    );
+
+    Ok(())
+}
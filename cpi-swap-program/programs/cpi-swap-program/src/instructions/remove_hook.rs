@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{CONFIG_SEED, HOOK_REGISTRY_SEED},
+    errors::CpiSwapError,
+    state::{HookRegistry, VaultConfig},
+};
+
+#[derive(Accounts)]
+pub struct RemoveHook<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      mut,
+      seeds = [HOOK_REGISTRY_SEED],
+      bump = hook_registry.bump,
+    )]
+    pub hook_registry: Account<'info, HookRegistry>,
+}
+
+pub fn handle_remove_hook(ctx: Context<RemoveHook>, program_id: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.hook_registry;
+    let len_before = registry.hooks.len();
+    registry
+        .hooks
+        .retain(|entry| entry.program_id != program_id);
+    require_neq!(
+        len_before,
+        registry.hooks.len(),
+        CpiSwapError::HookNotAllowed
+    );
+
+    Ok(())
+}
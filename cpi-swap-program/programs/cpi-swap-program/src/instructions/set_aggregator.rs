@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        AGGREGATOR_REGISTRY_SEED, CONFIG_SEED, MAX_AGGREGATORS, MAX_AGGREGATOR_DISCRIMINATORS,
+    },
+    errors::CpiSwapError,
+    state::{AggregatorEntry, AggregatorRegistry, RouteDecoder, VaultConfig},
+};
+
+#[derive(Accounts)]
+pub struct SetAggregator<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      init_if_needed,
+      payer = admin,
+      space = 8 + AggregatorRegistry::INIT_SPACE,
+      seeds = [AGGREGATOR_REGISTRY_SEED],
+      bump
+    )]
+    pub aggregator_registry: Account<'info, AggregatorRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_set_aggregator(
+    ctx: Context<SetAggregator>,
+    program_id: Pubkey,
+    decoder: RouteDecoder,
+    discriminators: Vec<[u8; 8]>,
+) -> Result<()> {
+    require_gte!(
+        MAX_AGGREGATOR_DISCRIMINATORS,
+        discriminators.len(),
+        CpiSwapError::TooManyDiscriminators
+    );
+
+    let registry = &mut ctx.accounts.aggregator_registry;
+    registry.bump = ctx.bumps.aggregator_registry;
+
+    match registry
+        .aggregators
+        .iter_mut()
+        .find(|entry| entry.program_id == program_id)
+    {
+        Some(entry) => {
+            entry.decoder = decoder;
+            entry.discriminators = discriminators;
+        }
+        None => {
+            require_gt!(
+                MAX_AGGREGATORS,
+                registry.aggregators.len(),
+                CpiSwapError::TooManyAggregators
+            );
+            registry.aggregators.push(AggregatorEntry {
+                program_id,
+                decoder,
+                discriminators,
+            });
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    constants::{DELEGATION_TERMS_SEED, STRATEGY_SEED, VAULT_SEED},
+    state::{DelegationTerms, StrategyConfig},
+};
+
+/// Lets a user set the price floor for delegated swaps out of their token
+/// account. Operators cannot execute delegated swaps of a pair until the
+/// user has set its terms.
+#[derive(Accounts)]
+pub struct SetDelegationTerms<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub input_mint: InterfaceAccount<'info, Mint>,
+    pub output_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      init_if_needed,
+      payer = user,
+      space = 8 + DelegationTerms::INIT_SPACE,
+      seeds = [
+        DELEGATION_TERMS_SEED,
+        vault.key().as_ref(),
+        user.key().as_ref(),
+        input_mint.key().as_ref(),
+        output_mint.key().as_ref(),
+      ],
+      bump
+    )]
+    pub delegation_terms: Account<'info, DelegationTerms>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_set_delegation_terms(ctx: Context<SetDelegationTerms>, min_price: u64) -> Result<()> {
+    let delegation_terms = &mut ctx.accounts.delegation_terms;
+    delegation_terms.user = ctx.accounts.user.key();
+    delegation_terms.vault = ctx.accounts.vault.key();
+    delegation_terms.input_mint = ctx.accounts.input_mint.key();
+    delegation_terms.output_mint = ctx.accounts.output_mint.key();
+    delegation_terms.min_price = min_price;
+    delegation_terms.bump = ctx.bumps.delegation_terms;
+
+    Ok(())
+}
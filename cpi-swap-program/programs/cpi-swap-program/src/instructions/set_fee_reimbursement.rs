@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{CONFIG_SEED, FEE_REIMBURSEMENT_SEED, STRATEGY_SEED, VAULT_SEED},
+    errors::CpiSwapError,
+    state::{FeeReimbursement, StrategyConfig, VaultConfig},
+};
+
+#[derive(Accounts)]
+pub struct SetFeeReimbursement<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      init_if_needed,
+      payer = admin,
+      space = 8 + FeeReimbursement::INIT_SPACE,
+      seeds = [FEE_REIMBURSEMENT_SEED, vault.key().as_ref()],
+      bump
+    )]
+    pub fee_reimbursement: Account<'info, FeeReimbursement>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_set_fee_reimbursement(
+    ctx: Context<SetFeeReimbursement>,
+    lamports_per_swap: u64,
+    max_lamports_per_day: u64,
+) -> Result<()> {
+    let fee_reimbursement = &mut ctx.accounts.fee_reimbursement;
+    fee_reimbursement.vault = ctx.accounts.vault.key();
+    fee_reimbursement.lamports_per_swap = lamports_per_swap;
+    fee_reimbursement.max_lamports_per_day = max_lamports_per_day;
+    fee_reimbursement.bump = ctx.bumps.fee_reimbursement;
+
+    Ok(())
+}
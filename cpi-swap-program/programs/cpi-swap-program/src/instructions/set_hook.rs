@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        CONFIG_SEED, HOOK_REGISTRY_SEED, MAX_HOOKS, MAX_HOOK_DISCRIMINATORS,
+        MAX_HOOK_WRITABLE_ACCOUNTS,
+    },
+    errors::CpiSwapError,
+    state::{HookEntry, HookRegistry, VaultConfig},
+};
+
+#[derive(Accounts)]
+pub struct SetHook<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      init_if_needed,
+      payer = admin,
+      space = 8 + HookRegistry::INIT_SPACE,
+      seeds = [HOOK_REGISTRY_SEED],
+      bump
+    )]
+    pub hook_registry: Account<'info, HookRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_set_hook(
+    ctx: Context<SetHook>,
+    program_id: Pubkey,
+    discriminators: Vec<[u8; 8]>,
+    writable_accounts: Vec<Pubkey>,
+) -> Result<()> {
+    require_gte!(
+        MAX_HOOK_DISCRIMINATORS,
+        discriminators.len(),
+        CpiSwapError::TooManyDiscriminators
+    );
+    require_gte!(
+        MAX_HOOK_WRITABLE_ACCOUNTS,
+        writable_accounts.len(),
+        CpiSwapError::TooManyHookAccounts
+    );
+
+    let registry = &mut ctx.accounts.hook_registry;
+    registry.bump = ctx.bumps.hook_registry;
+
+    match registry
+        .hooks
+        .iter_mut()
+        .find(|entry| entry.program_id == program_id)
+    {
+        Some(entry) => {
+            entry.discriminators = discriminators;
+            entry.writable_accounts = writable_accounts;
+        }
+        None => {
+            require_gt!(MAX_HOOKS, registry.hooks.len(), CpiSwapError::TooManyHooks);
+            registry.hooks.push(HookEntry {
+                program_id,
+                discriminators,
+                writable_accounts,
+            });
+        }
+    }
+
+    Ok(())
+}
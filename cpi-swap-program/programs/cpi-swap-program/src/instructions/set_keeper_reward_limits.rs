@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{CONFIG_SEED, STRATEGY_SEED},
+    errors::CpiSwapError,
+    state::{StrategyConfig, VaultConfig},
+};
+
+#[derive(Accounts)]
+pub struct SetKeeperRewardLimits<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      mut,
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+}
+
+/// Caps what orders on the strategy may pay keepers out of the vault.
+/// Zero caps disable keeper rewards.
+pub fn handle_set_keeper_reward_limits(
+    ctx: Context<SetKeeperRewardLimits>,
+    max_keeper_reward: u64,
+    max_order_keeper_rewards: u64,
+) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.max_keeper_reward = max_keeper_reward;
+    strategy.max_order_keeper_rewards = max_order_keeper_rewards;
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+use crate::{
+    constants::{CONFIG_SEED, MAX_NAV_ASSETS, STRATEGY_SEED, VAULT_NAV_SEED, VAULT_SEED},
+    errors::CpiSwapError,
+    state::{NavAsset, StrategyConfig, VaultConfig, VaultNav},
+    utils::unpack_mint,
+};
+
+/// Pyth price update account that prices `mint` in the NAV's quote currency.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NavAssetOracle {
+    pub mint: Pubkey,
+    pub oracle: Pubkey,
+    pub feed_id: [u8; 32],
+}
+
+/// Replaces the vault's NAV assets. The mints are passed as remaining
+/// accounts in the order of `assets`, so that their decimals and the vault
+/// ATAs are taken from chain instead of the caller.
+#[derive(Accounts)]
+pub struct SetNavAssets<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      init_if_needed,
+      payer = admin,
+      space = 8 + VaultNav::INIT_SPACE,
+      seeds = [VAULT_NAV_SEED, vault.key().as_ref()],
+      bump
+    )]
+    pub vault_nav: Account<'info, VaultNav>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_set_nav_assets(
+    ctx: Context<SetNavAssets>,
+    assets: Vec<NavAssetOracle>,
+    max_price_age: u64,
+    max_confidence_bps: u16,
+) -> Result<()> {
+    require_gte!(MAX_NAV_ASSETS, assets.len(), CpiSwapError::TooManyNavAssets);
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        assets.len(),
+        CpiSwapError::MissingNavAccounts
+    );
+
+    let vault = ctx.accounts.vault.key();
+    let nav_assets = assets
+        .iter()
+        .zip(ctx.remaining_accounts)
+        .map(|(asset, mint_account)| {
+            require_keys_eq!(
+                asset.mint,
+                *mint_account.key,
+                CpiSwapError::InvalidNavAccount
+            );
+            let mint = unpack_mint(mint_account).ok_or(CpiSwapError::InvalidNavAccount)?;
+            Ok(NavAsset {
+                mint: asset.mint,
+                token_account: get_associated_token_address_with_program_id(
+                    &vault,
+                    &asset.mint,
+                    mint_account.owner,
+                ),
+                oracle: asset.oracle,
+                feed_id: asset.feed_id,
+                decimals: mint.decimals,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let vault_nav = &mut ctx.accounts.vault_nav;
+    vault_nav.vault = vault;
+    vault_nav.assets = nav_assets;
+    vault_nav.max_price_age = max_price_age;
+    vault_nav.max_confidence_bps = max_confidence_bps;
+    // A NAV over the previous assets must not be read as current
+    vault_nav.nav = 0;
+    vault_nav.updated_at = 0;
+    vault_nav.assets_set_at = Clock::get()?.unix_timestamp;
+    vault_nav.bump = ctx.bumps.vault_nav;
+
+    Ok(())
+}
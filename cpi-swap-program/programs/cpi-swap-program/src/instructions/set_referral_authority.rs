@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::CONFIG_SEED, errors::CpiSwapError, state::VaultConfig};
+
+#[derive(Accounts)]
+pub struct SetReferralAuthority<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+      mut,
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+}
+
+pub fn handle_set_referral_authority(
+    ctx: Context<SetReferralAuthority>,
+    referral_authority: Pubkey,
+) -> Result<()> {
+    ctx.accounts.config.referral_authority = referral_authority;
+
+    Ok(())
+}
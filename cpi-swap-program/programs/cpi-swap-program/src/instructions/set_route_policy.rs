@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{CONFIG_SEED, MAX_POLICY_VENUES, ROUTE_POLICY_SEED, STRATEGY_SEED, VAULT_SEED},
+    errors::CpiSwapError,
+    state::{RoutePolicy, StrategyConfig, VaultConfig, VenueFilter},
+};
+
+#[derive(Accounts)]
+pub struct SetRoutePolicy<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      init_if_needed,
+      payer = admin,
+      space = 8 + RoutePolicy::INIT_SPACE,
+      seeds = [ROUTE_POLICY_SEED, vault.key().as_ref()],
+      bump
+    )]
+    pub route_policy: Account<'info, RoutePolicy>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_set_route_policy(
+    ctx: Context<SetRoutePolicy>,
+    max_hops: u8,
+    max_slippage_bps: u16,
+    venue_filter: VenueFilter,
+    venues: Vec<u8>,
+    allow_undecoded_routes: bool,
+) -> Result<()> {
+    require_gte!(MAX_POLICY_VENUES, venues.len(), CpiSwapError::TooManyVenues);
+
+    ctx.accounts.route_policy.set_inner(RoutePolicy {
+        vault: ctx.accounts.vault.key(),
+        max_hops,
+        max_slippage_bps,
+        venue_filter,
+        venues,
+        allow_undecoded_routes,
+        bump: ctx.bumps.route_policy,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{CONFIG_SEED, MAX_RECIPIENTS, MAX_STRATEGY_MINTS, STRATEGY_SEED, VAULT_SEED},
+    errors::CpiSwapError,
+    state::{StrategyConfig, VaultConfig},
+};
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64)]
+pub struct SetStrategy<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      init_if_needed,
+      payer = admin,
+      space = 8 + StrategyConfig::INIT_SPACE,
+      seeds = [STRATEGY_SEED, &strategy_id.to_le_bytes()],
+      bump
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy_id.to_le_bytes()],
+      bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates or updates the strategy's risk limits. Roles are set separately
+/// with `set_strategy_roles`.
+pub fn handle_set_strategy(
+    ctx: Context<SetStrategy>,
+    strategy_id: u64,
+    allowed_mints: Vec<Pubkey>,
+    recipients: Vec<Pubkey>,
+    max_slippage_bps: u16,
+    platform_fee_bps: u8,
+) -> Result<()> {
+    require_gte!(
+        MAX_STRATEGY_MINTS,
+        allowed_mints.len(),
+        CpiSwapError::TooManyMints
+    );
+    require_gte!(
+        MAX_RECIPIENTS,
+        recipients.len(),
+        CpiSwapError::TooManyRecipients
+    );
+
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.strategy_id = strategy_id;
+    strategy.allowed_mints = allowed_mints;
+    strategy.recipients = recipients;
+    strategy.max_slippage_bps = max_slippage_bps;
+    strategy.platform_fee_bps = platform_fee_bps;
+    strategy.vault_bump = ctx.bumps.vault;
+    strategy.bump = ctx.bumps.strategy;
+
+    Ok(())
+}
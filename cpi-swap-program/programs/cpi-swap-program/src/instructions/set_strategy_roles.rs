@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{CONFIG_SEED, MAX_KEEPERS, MAX_MANAGERS, STRATEGY_SEED},
+    errors::CpiSwapError,
+    state::{StrategyConfig, VaultConfig},
+};
+
+#[derive(Accounts)]
+pub struct SetStrategyRoles<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      mut,
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+}
+
+pub fn handle_set_strategy_roles(
+    ctx: Context<SetStrategyRoles>,
+    managers: Vec<Pubkey>,
+    keepers: Vec<Pubkey>,
+) -> Result<()> {
+    require_gte!(
+        MAX_MANAGERS,
+        managers.len(),
+        CpiSwapError::TooManyRoleMembers
+    );
+    require_gte!(MAX_KEEPERS, keepers.len(), CpiSwapError::TooManyRoleMembers);
+
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.managers = managers;
+    strategy.keepers = keepers;
+
+    Ok(())
+}
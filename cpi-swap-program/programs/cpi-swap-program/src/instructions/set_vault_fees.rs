@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+use crate::{
+    constants::{
+        CONFIG_SEED, SHARE_DECIMALS, SHARE_MINT_SEED, STRATEGY_SEED, VAULT_FEES_SEED, VAULT_SEED,
+    },
+    errors::CpiSwapError,
+    state::{StrategyConfig, VaultConfig, VaultFees},
+};
+
+#[derive(Accounts)]
+pub struct SetVaultFees<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      init_if_needed,
+      payer = admin,
+      space = 8 + VaultFees::INIT_SPACE,
+      seeds = [VAULT_FEES_SEED, vault.key().as_ref()],
+      bump
+    )]
+    pub vault_fees: Account<'info, VaultFees>,
+
+    /// The vault's shares, minted by the vault.
+    #[account(
+      init_if_needed,
+      payer = admin,
+      seeds = [SHARE_MINT_SEED, vault.key().as_ref()],
+      bump,
+      mint::decimals = SHARE_DECIMALS,
+      mint::authority = vault,
+      mint::token_program = token_program,
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Sets the vault's fee rates and recipient, creating its share mint on
+/// first use. New rates apply from the last accrual, so accrue right before
+/// changing them.
+pub fn handle_set_vault_fees(
+    ctx: Context<SetVaultFees>,
+    fee_recipient: Pubkey,
+    management_fee_bps: u16,
+    performance_fee_bps: u16,
+) -> Result<()> {
+    require_gte!(10_000, management_fee_bps, CpiSwapError::InvalidVaultFee);
+    require_gte!(10_000, performance_fee_bps, CpiSwapError::InvalidVaultFee);
+
+    let vault_fees = &mut ctx.accounts.vault_fees;
+    vault_fees.vault = ctx.accounts.vault.key();
+    vault_fees.share_mint = ctx.accounts.share_mint.key();
+    vault_fees.fee_recipient = fee_recipient;
+    vault_fees.management_fee_bps = management_fee_bps;
+    vault_fees.performance_fee_bps = performance_fee_bps;
+    vault_fees.bump = ctx.bumps.vault_fees;
+
+    Ok(())
+}
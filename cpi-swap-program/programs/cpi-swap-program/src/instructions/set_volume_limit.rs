@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    constants::{CONFIG_SEED, STRATEGY_SEED, VAULT_SEED, VOLUME_LIMIT_SEED},
+    errors::CpiSwapError,
+    state::{SlidingVolume, StrategyConfig, VaultConfig, VolumeLimit},
+};
+
+#[derive(Accounts)]
+pub struct SetVolumeLimit<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+      init_if_needed,
+      payer = admin,
+      space = 8 + VolumeLimit::INIT_SPACE,
+      seeds = [VOLUME_LIMIT_SEED, vault.key().as_ref(), mint.key().as_ref()],
+      bump
+    )]
+    pub volume_limit: Account<'info, VolumeLimit>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_set_volume_limit(
+    ctx: Context<SetVolumeLimit>,
+    window_seconds: i64,
+    max_vault_volume: u64,
+    max_operator_volume: u64,
+) -> Result<()> {
+    require_gt!(window_seconds, 0, CpiSwapError::InvalidVolumeWindow);
+
+    let volume_limit = &mut ctx.accounts.volume_limit;
+    if volume_limit.window_seconds != window_seconds {
+        volume_limit.volume = SlidingVolume::default();
+    }
+    volume_limit.vault = ctx.accounts.vault.key();
+    volume_limit.mint = ctx.accounts.mint.key();
+    volume_limit.window_seconds = window_seconds;
+    volume_limit.max_vault_volume = max_vault_volume;
+    volume_limit.max_operator_volume = max_operator_volume;
+    volume_limit.bump = ctx.bumps.volume_limit;
+
+    Ok(())
+}
@@ -0,0 +1,447 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    aggregator::{
+        check_vault_writable_accounts, invoke_route, pay_from_vault, settle_vault_route,
+        spare_vault_lamports, PlatformFee, VaultRoute,
+    },
+    constants::{
+        AGGREGATOR_REGISTRY_SEED, CONFIG_SEED, FEE_REIMBURSEMENT_SEED, HOOK_REGISTRY_SEED,
+        OPERATOR_VOLUME_SEED, ROUTE_POLICY_SEED, STRATEGY_SEED, VAULT_SEED, VAULT_STATS_SEED,
+        VOLUME_LIMIT_SEED,
+    },
+    errors::CpiSwapError,
+    events::{FeeReimbursedEvent, OutputSentEvent, SwapEvent},
+    state::{
+        AggregatorRegistry, FeeReimbursement, HookRegistry, OperatorVolume, RoutePolicy,
+        StrategyConfig, VaultConfig, VaultStats, VolumeLimit,
+    },
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SwapResult {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+}
+
+/// Balances captured before a swap's route is invoked.
+pub struct SwapBalances {
+    pub input: u64,
+    pub output: u64,
+    pub platform_fee: Option<u64>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(constraint = strategy.allows_mint(&input_mint.key()) @ CpiSwapError::MintNotAllowed)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+    pub input_mint_program: Interface<'info, TokenInterface>,
+    #[account(constraint = strategy.allows_mint(&output_mint.key()) @ CpiSwapError::MintNotAllowed)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+    pub output_mint_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+      constraint = strategy.is_manager(&operator.key()) @ CpiSwapError::UnauthorizedManager,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      mut,
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=input_mint_program,
+    )]
+    pub vault_input_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+      mut,
+      associated_token::mint=output_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=output_mint_program,
+    )]
+    pub vault_output_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Referral token account that collects the route's platform fee.
+    #[account(
+      constraint = platform_fee_account.owner == config.referral_authority
+        @ CpiSwapError::InvalidPlatformFeeAccount,
+    )]
+    pub platform_fee_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Receives the realized output after the swap instead of the vault.
+    #[account(
+      mut,
+      token::mint = output_mint,
+      token::token_program = output_mint_program,
+      constraint = strategy.allows_recipient(&recipient_token_account.owner)
+        @ CpiSwapError::RecipientNotAllowed,
+    )]
+    pub recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: the vault's `VolumeLimit` for the input mint, if the admin set
+    /// one. Unset mints have no account here and are not capped.
+    #[account(
+      mut,
+      seeds = [VOLUME_LIMIT_SEED, vault.key().as_ref(), input_mint.key().as_ref()],
+      bump,
+    )]
+    pub input_volume_limit: UncheckedAccount<'info>,
+
+    /// Required when the input mint has a volume limit.
+    #[account(
+      init_if_needed,
+      payer = operator,
+      space = 8 + OperatorVolume::INIT_SPACE,
+      seeds = [OPERATOR_VOLUME_SEED, input_volume_limit.key().as_ref(), operator.key().as_ref()],
+      bump
+    )]
+    pub operator_volume: Option<Account<'info, OperatorVolume>>,
+
+    #[account(
+      init_if_needed,
+      payer = operator,
+      space = 8 + VaultStats::INIT_SPACE,
+      seeds = [VAULT_STATS_SEED, vault.key().as_ref()],
+      bump
+    )]
+    pub vault_stats: Account<'info, VaultStats>,
+
+    /// Pays the operator back for transaction fees out of the vault's SOL.
+    #[account(
+      mut,
+      seeds = [FEE_REIMBURSEMENT_SEED, vault.key().as_ref()],
+      bump = fee_reimbursement.bump,
+    )]
+    pub fee_reimbursement: Option<Account<'info, FeeReimbursement>>,
+
+    #[account(
+      seeds = [AGGREGATOR_REGISTRY_SEED],
+      bump = aggregator_registry.bump,
+    )]
+    pub aggregator_registry: Account<'info, AggregatorRegistry>,
+
+    #[account(
+      seeds = [ROUTE_POLICY_SEED, vault.key().as_ref()],
+      bump = route_policy.bump,
+    )]
+    pub route_policy: Account<'info, RoutePolicy>,
+
+    /// CHECK: checked against `aggregator_registry` before invoking
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+
+    /// Only needed by `swap_with_hook`.
+    #[account(
+      seeds = [HOOK_REGISTRY_SEED],
+      bump = hook_registry.bump,
+    )]
+    pub hook_registry: Option<Account<'info, HookRegistry>>,
+
+    /// CHECK: checked against `hook_registry` before invoking
+    #[account(executable)]
+    pub hook_program: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Swap<'info> {
+    pub fn balances(&self) -> SwapBalances {
+        SwapBalances {
+            input: self.vault_input_token_account.amount,
+            output: self.vault_output_token_account.amount,
+            platform_fee: self.platform_fee_account.as_ref().map(|acc| acc.amount),
+        }
+    }
+
+    /// Invokes the route with the vault as signer and returns the least
+    /// output the vault must receive.
+    pub fn invoke_jupiter(&self, remaining_accounts: &[AccountInfo], data: Vec<u8>) -> Result<u64> {
+        VaultRoute {
+            aggregator_registry: &self.aggregator_registry,
+            route_policy: &self.route_policy,
+            strategy: &self.strategy,
+            aggregator_program: self.aggregator_program.key(),
+            vault: self.vault.to_account_info(),
+            writable: &[
+                self.vault_input_token_account.key(),
+                self.vault_output_token_account.key(),
+            ],
+            destination: self.vault_output_token_account.key(),
+            platform_fee: self.platform_fee_account.as_ref().map(|acc| PlatformFee {
+                account: acc.key(),
+                bps: self.strategy.platform_fee_bps,
+                referral_authority: self.config.referral_authority,
+                input_mint: self.input_mint.key(),
+                output_mint: self.output_mint.key(),
+            }),
+        }
+        .invoke(remaining_accounts, data)
+    }
+
+    /// Forwards `amount` of output to `recipient_token_account`, if one was
+    /// passed, and returns the account it was sent to.
+    pub fn send_output(&self, amount: u64) -> Result<Option<Pubkey>> {
+        let Some(recipient_token_account) = &self.recipient_token_account else {
+            return Ok(None);
+        };
+
+        let strategy_id = self.strategy.strategy_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &strategy_id, &[self.strategy.vault_bump]]];
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.output_mint_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault_output_token_account.to_account_info(),
+                    mint: self.output_mint.to_account_info(),
+                    to: recipient_token_account.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            self.output_mint.decimals,
+        )?;
+
+        Ok(Some(recipient_token_account.key()))
+    }
+
+    /// Invokes the hook program with the vault as signer. The hook may spend
+    /// at most `max_spend` from the vault output token account and nothing
+    /// from the input token account or the vault's lamports. It may not write
+    /// to any other token account the vault owns or is delegate of, nor to any
+    /// account not pinned for it in the hook registry, so what it spends can
+    /// only go where the admin allowed. Returns the amount of output it spent.
+    pub fn invoke_hook(
+        &mut self,
+        hook_accounts: &[AccountInfo],
+        hook_data: Vec<u8>,
+        max_spend: u64,
+    ) -> Result<u64> {
+        let (Some(hook_registry), Some(hook_program)) = (&self.hook_registry, &self.hook_program)
+        else {
+            return err!(CpiSwapError::MissingHookAccounts);
+        };
+        let hook = hook_registry.check(hook_program.key, &hook_data)?;
+        let hook_program = hook_program.key();
+        check_vault_writable_accounts(
+            hook_accounts,
+            &self.vault.key(),
+            &[self.vault_output_token_account.key()],
+        )?;
+        hook.check_writable_accounts(
+            hook_accounts,
+            &[self.vault.key(), self.vault_output_token_account.key()],
+        )?;
+
+        let input_before = self.vault_input_token_account.amount;
+        let output_before = self.vault_output_token_account.amount;
+        let vault_lamports_before = self.vault.lamports();
+
+        let strategy_id = self.strategy.strategy_id.to_le_bytes();
+        invoke_route(
+            hook_program,
+            hook_accounts,
+            hook_data,
+            Some(&self.vault.key()),
+            &[&[VAULT_SEED, &strategy_id, &[self.strategy.vault_bump]]],
+        )?;
+
+        require_eq!(
+            vault_lamports_before,
+            self.vault.lamports(),
+            CpiSwapError::VaultLamportsChanged
+        );
+
+        self.vault_input_token_account.reload()?;
+        self.vault_output_token_account.reload()?;
+        require_eq!(
+            input_before,
+            self.vault_input_token_account.amount,
+            CpiSwapError::HookSpendExceeded
+        );
+        let spent = output_before.saturating_sub(self.vault_output_token_account.amount);
+        require_gte!(max_spend, spent, CpiSwapError::HookSpendExceeded);
+
+        Ok(spent)
+    }
+
+    /// Adds this swap, and the platform fee it paid if a fee account was
+    /// passed, to the vault's lifetime stats.
+    pub fn record_stats(
+        &mut self,
+        amount_in: u64,
+        amount_out: u64,
+        fee_before: Option<u64>,
+        vault_stats_bump: u8,
+    ) -> Result<()> {
+        let fee = match &mut self.platform_fee_account {
+            Some(platform_fee_account) => {
+                platform_fee_account.reload()?;
+                let fee = platform_fee_account
+                    .amount
+                    .saturating_sub(fee_before.unwrap_or_default());
+                Some((platform_fee_account.mint, fee))
+            }
+            None => None,
+        };
+
+        let vault_stats = &mut self.vault_stats;
+        vault_stats.init_if_needed(self.vault.key(), vault_stats_bump);
+        vault_stats.record_swap(
+            self.input_mint.key(),
+            self.output_mint.key(),
+            amount_in,
+            amount_out,
+            Clock::get()?.slot,
+        )?;
+        if let Some((mint, fee)) = fee {
+            vault_stats.record_fee(mint, fee);
+        }
+
+        Ok(())
+    }
+
+    /// Pays the operator from the vault's lamports within the configured caps,
+    /// never taking the vault below rent exemption. Returns the amount paid.
+    pub fn reimburse_operator(&mut self) -> Result<u64> {
+        let Some(fee_reimbursement) = &mut self.fee_reimbursement else {
+            return Ok(0);
+        };
+
+        let spare = spare_vault_lamports(&self.vault)?;
+        let lamports = fee_reimbursement.claim(Clock::get()?.unix_timestamp, spare);
+        if lamports == 0 {
+            return Ok(0);
+        }
+
+        pay_from_vault(
+            &self.system_program,
+            &self.vault,
+            &self.strategy,
+            self.operator.to_account_info(),
+            lamports,
+        )?;
+
+        Ok(lamports)
+    }
+
+    pub fn record_volume(
+        &mut self,
+        amount_in: u64,
+        operator_volume_bump: Option<u8>,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let Some(input_volume_limit) =
+            VolumeLimit::record_at(&self.input_volume_limit, now, amount_in)?
+        else {
+            return Ok(());
+        };
+
+        let (Some(operator_volume), Some(operator_volume_bump)) =
+            (self.operator_volume.as_mut(), operator_volume_bump)
+        else {
+            return err!(CpiSwapError::OperatorVolumeRequired);
+        };
+        if operator_volume.operator == Pubkey::default() {
+            operator_volume.operator = self.operator.key();
+            operator_volume.volume_limit = self.input_volume_limit.key();
+            operator_volume.bump = operator_volume_bump;
+        }
+        operator_volume.record(
+            now,
+            amount_in,
+            input_volume_limit.window_seconds,
+            input_volume_limit.max_operator_volume,
+        )
+    }
+}
+
+/// Bookkeeping every `Swap` handler runs after the route: settles the swap
+/// against `before`, records volume and stats and reimburses the operator.
+/// Takes the whole context because the reimbursement is emitted as a CPI
+/// event. Returns the realized `(amount_in, amount_out)`.
+pub fn settle_swap(
+    ctx: &mut Context<Swap>,
+    before: SwapBalances,
+    min_amount_out: u64,
+) -> Result<(u64, u64)> {
+    let (amount_in, amount_out) = settle_vault_route(
+        &mut ctx.accounts.vault_input_token_account,
+        &mut ctx.accounts.vault_output_token_account,
+        before.input,
+        before.output,
+        min_amount_out,
+    )?;
+    ctx.accounts
+        .record_volume(amount_in, ctx.bumps.operator_volume)?;
+    ctx.accounts.record_stats(
+        amount_in,
+        amount_out,
+        before.platform_fee,
+        ctx.bumps.vault_stats,
+    )?;
+
+    let reimbursed = ctx.accounts.reimburse_operator()?;
+    if reimbursed > 0 {
+        emit_cpi!(FeeReimbursedEvent {
+            operator: ctx.accounts.operator.key(),
+            vault: ctx.accounts.vault.key(),
+            lamports: reimbursed,
+        });
+    }
+
+    Ok((amount_in, amount_out))
+}
+
+pub fn handle_swap(mut ctx: Context<Swap>, data: Vec<u8>) -> Result<SwapResult> {
+    let before = ctx.accounts.balances();
+    let min_amount_out = ctx.accounts.invoke_jupiter(ctx.remaining_accounts, data)?;
+    let (amount_in, amount_out) = settle_swap(&mut ctx, before, min_amount_out)?;
+
+    if let Some(recipient_token_account) = ctx.accounts.send_output(amount_out)? {
+        emit_cpi!(OutputSentEvent {
+            recipient_token_account,
+            mint: ctx.accounts.output_mint.key(),
+            amount: amount_out,
+        });
+    }
+
+    emit_cpi!(SwapEvent {
+        operator: ctx.accounts.operator.key(),
+        input_mint: ctx.accounts.input_mint.key(),
+        output_mint: ctx.accounts.output_mint.key(),
+        amount_in,
+        amount_out,
+    });
+
+    Ok(SwapResult {
+        amount_in,
+        amount_out,
+        input_mint: ctx.accounts.input_mint.key(),
+        output_mint: ctx.accounts.output_mint.key(),
+    })
+}
@@ -0,0 +1,164 @@
+use anchor_lang::{prelude::*, solana_program::program_option::COption};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    aggregator::VaultRoute,
+    constants::{
+        AGGREGATOR_REGISTRY_SEED, DELEGATION_TERMS_SEED, ROUTE_POLICY_SEED, STRATEGY_SEED,
+        VAULT_SEED,
+    },
+    errors::CpiSwapError,
+    events::DelegatedSwapEvent,
+    state::{AggregatorRegistry, DelegationTerms, RoutePolicy, StrategyConfig},
+    SwapResult,
+};
+
+/// Swaps out of a user's token account on which the user has approved the
+/// vault as delegate, delivering the output back to the user's ATA. The SPL
+/// delegated amount bounds how much an operator can ever spend, and the
+/// user's [`DelegationTerms`] bound the price it is spent at.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SwapDelegated<'info> {
+    pub operator: Signer<'info>,
+
+    /// CHECK: only used as the owner of the user token accounts
+    pub user: UncheckedAccount<'info>,
+
+    #[account(constraint = strategy.allows_mint(&input_mint.key()) @ CpiSwapError::MintNotAllowed)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+    pub input_mint_program: Interface<'info, TokenInterface>,
+    #[account(constraint = strategy.allows_mint(&output_mint.key()) @ CpiSwapError::MintNotAllowed)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+    pub output_mint_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+      constraint = strategy.is_manager(&operator.key()) @ CpiSwapError::UnauthorizedManager,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      token::mint = input_mint,
+      token::authority = user,
+      token::token_program = input_mint_program,
+      constraint = user_input_token_account.delegate == COption::Some(vault.key())
+        @ CpiSwapError::VaultNotDelegate,
+    )]
+    pub user_input_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+      mut,
+      associated_token::mint = output_mint,
+      associated_token::authority = user,
+      associated_token::token_program = output_mint_program,
+    )]
+    pub user_output_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+      seeds = [
+        DELEGATION_TERMS_SEED,
+        vault.key().as_ref(),
+        user.key().as_ref(),
+        input_mint.key().as_ref(),
+        output_mint.key().as_ref(),
+      ],
+      bump = delegation_terms.bump,
+    )]
+    pub delegation_terms: Account<'info, DelegationTerms>,
+
+    #[account(
+      seeds = [AGGREGATOR_REGISTRY_SEED],
+      bump = aggregator_registry.bump,
+    )]
+    pub aggregator_registry: Account<'info, AggregatorRegistry>,
+
+    #[account(
+      seeds = [ROUTE_POLICY_SEED, vault.key().as_ref()],
+      bump = route_policy.bump,
+    )]
+    pub route_policy: Account<'info, RoutePolicy>,
+
+    /// CHECK: checked against `aggregator_registry` before invoking
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+}
+
+impl<'info> SwapDelegated<'info> {
+    pub fn invoke_jupiter(&self, remaining_accounts: &[AccountInfo], data: Vec<u8>) -> Result<()> {
+        VaultRoute {
+            aggregator_registry: &self.aggregator_registry,
+            route_policy: &self.route_policy,
+            strategy: &self.strategy,
+            aggregator_program: self.aggregator_program.key(),
+            vault: self.vault.to_account_info(),
+            writable: &[self.user_input_token_account.key()],
+            destination: self.user_output_token_account.key(),
+            platform_fee: None,
+        }
+        .invoke(remaining_accounts, data)?;
+
+        Ok(())
+    }
+
+    /// Reloads both user token accounts and returns the realized
+    /// `(amount_in, amount_out)` against the balances captured before the CPI.
+    pub fn settle(&mut self, input_before: u64, output_before: u64) -> Result<(u64, u64)> {
+        self.user_input_token_account.reload()?;
+        self.user_output_token_account.reload()?;
+
+        let amount_in = input_before.saturating_sub(self.user_input_token_account.amount);
+        let amount_out = self
+            .user_output_token_account
+            .amount
+            .saturating_sub(output_before);
+
+        Ok((amount_in, amount_out))
+    }
+}
+
+pub fn handle_swap_delegated(
+    ctx: Context<SwapDelegated>,
+    data: Vec<u8>,
+    min_amount_out: u64,
+) -> Result<SwapResult> {
+    let input_before = ctx.accounts.user_input_token_account.amount;
+    let output_before = ctx.accounts.user_output_token_account.amount;
+
+    ctx.accounts.invoke_jupiter(ctx.remaining_accounts, data)?;
+
+    let (amount_in, amount_out) = ctx.accounts.settle(input_before, output_before)?;
+    require_gte!(
+        amount_out,
+        min_amount_out,
+        CpiSwapError::MinimumOutputNotMet
+    );
+    ctx.accounts
+        .delegation_terms
+        .check_price(amount_in, amount_out)?;
+
+    emit_cpi!(DelegatedSwapEvent {
+        operator: ctx.accounts.operator.key(),
+        user: ctx.accounts.user.key(),
+        input_mint: ctx.accounts.input_mint.key(),
+        output_mint: ctx.accounts.output_mint.key(),
+        amount_in,
+        amount_out,
+        remaining_allowance: ctx.accounts.user_input_token_account.delegated_amount,
+    });
+
+    Ok(SwapResult {
+        amount_in,
+        amount_out,
+        input_mint: ctx.accounts.input_mint.key(),
+        output_mint: ctx.accounts.output_mint.key(),
+    })
+}
@@ -0,0 +1,65 @@
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::{
+    errors::CpiSwapError,
+    events::{OutputSentEvent, SwapExactOutEvent},
+    jupiter_aggregator::client::args::{ExactOutRoute, SharedAccountsExactOutRoute},
+    settle_swap, Swap,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SwapExactOutResult {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub unused_input: u64,
+}
+
+pub fn handle_swap_exact_out(
+    mut ctx: Context<Swap>,
+    data: Vec<u8>,
+    out_amount: u64,
+    max_in: u64,
+) -> Result<SwapExactOutResult> {
+    let discriminator = data.get(..8).ok_or(CpiSwapError::InvalidRouteInstruction)?;
+    require!(
+        discriminator == ExactOutRoute::DISCRIMINATOR
+            || discriminator == SharedAccountsExactOutRoute::DISCRIMINATOR,
+        CpiSwapError::InvalidRouteInstruction
+    );
+
+    let before = ctx.accounts.balances();
+    let min_amount_out = ctx.accounts.invoke_jupiter(ctx.remaining_accounts, data)?;
+    let (amount_in, amount_out) = settle_swap(&mut ctx, before, min_amount_out)?;
+    require_gte!(
+        amount_out,
+        out_amount,
+        CpiSwapError::OutputAmountNotReceived
+    );
+    require_gte!(max_in, amount_in, CpiSwapError::MaxInputExceeded);
+
+    let unused_input = max_in - amount_in;
+
+    if let Some(recipient_token_account) = ctx.accounts.send_output(amount_out)? {
+        emit_cpi!(OutputSentEvent {
+            recipient_token_account,
+            mint: ctx.accounts.output_mint.key(),
+            amount: amount_out,
+        });
+    }
+
+    emit_cpi!(SwapExactOutEvent {
+        operator: ctx.accounts.operator.key(),
+        input_mint: ctx.accounts.input_mint.key(),
+        output_mint: ctx.accounts.output_mint.key(),
+        amount_in,
+        amount_out,
+        max_in,
+        unused_input,
+    });
+
+    Ok(SwapExactOutResult {
+        amount_in,
+        amount_out,
+        unused_input,
+    })
+}
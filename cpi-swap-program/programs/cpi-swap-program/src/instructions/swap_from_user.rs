@@ -0,0 +1,165 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    aggregator::{check_platform_fee, invoke_route, validate_route, PlatformFee},
+    constants::{
+        AGGREGATOR_REGISTRY_SEED, CONFIG_SEED, ROUTE_POLICY_SEED, STRATEGY_SEED, VAULT_SEED,
+    },
+    errors::CpiSwapError,
+    events::UserSwapEvent,
+    state::{AggregatorRegistry, RoutePolicy, StrategyConfig, VaultConfig},
+    SwapResult,
+};
+
+/// Swaps between the signer's own token accounts under the vault's route
+/// policy. The program never signs, so nothing is held in custody.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SwapFromUser<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(constraint = strategy.allows_mint(&input_mint.key()) @ CpiSwapError::MintNotAllowed)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+    pub input_mint_program: Interface<'info, TokenInterface>,
+    #[account(constraint = strategy.allows_mint(&output_mint.key()) @ CpiSwapError::MintNotAllowed)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+    pub output_mint_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      token::mint = input_mint,
+      token::authority = user,
+      token::token_program = input_mint_program,
+    )]
+    pub user_input_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+      mut,
+      token::mint = output_mint,
+      token::authority = user,
+      token::token_program = output_mint_program,
+    )]
+    pub user_output_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+      constraint = platform_fee_account.owner == config.referral_authority
+        @ CpiSwapError::InvalidPlatformFeeAccount,
+    )]
+    pub platform_fee_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      seeds = [AGGREGATOR_REGISTRY_SEED],
+      bump = aggregator_registry.bump,
+    )]
+    pub aggregator_registry: Account<'info, AggregatorRegistry>,
+
+    #[account(
+      seeds = [ROUTE_POLICY_SEED, vault.key().as_ref()],
+      bump = route_policy.bump,
+    )]
+    pub route_policy: Account<'info, RoutePolicy>,
+
+    /// CHECK: checked against `aggregator_registry` before invoking
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+}
+
+pub fn handle_swap_from_user(
+    ctx: Context<SwapFromUser>,
+    data: Vec<u8>,
+    min_amount_out: u64,
+) -> Result<SwapResult> {
+    let route = validate_route(
+        &ctx.accounts.aggregator_registry,
+        &ctx.accounts.route_policy,
+        ctx.accounts.aggregator_program.key,
+        &data,
+    )?;
+    ctx.accounts.strategy.check_route(route.as_ref())?;
+    let platform_fee = ctx
+        .accounts
+        .platform_fee_account
+        .as_ref()
+        .map(|acc| PlatformFee {
+            account: acc.key(),
+            bps: ctx.accounts.strategy.platform_fee_bps,
+            referral_authority: ctx.accounts.config.referral_authority,
+            input_mint: ctx.accounts.input_mint.key(),
+            output_mint: ctx.accounts.output_mint.key(),
+        });
+    check_platform_fee(
+        route.as_ref(),
+        ctx.remaining_accounts,
+        platform_fee.as_ref(),
+    )?;
+    if let Some(route) = &route {
+        let authority = ctx
+            .remaining_accounts
+            .get(route.user_transfer_authority_index)
+            .ok_or(CpiSwapError::InvalidTransferAuthority)?;
+        require_keys_eq!(
+            *authority.key,
+            ctx.accounts.user.key(),
+            CpiSwapError::InvalidTransferAuthority
+        );
+    }
+
+    let input_before = ctx.accounts.user_input_token_account.amount;
+    let output_before = ctx.accounts.user_output_token_account.amount;
+
+    invoke_route(
+        ctx.accounts.aggregator_program.key(),
+        ctx.remaining_accounts,
+        data,
+        None,
+        &[],
+    )?;
+
+    ctx.accounts.user_input_token_account.reload()?;
+    ctx.accounts.user_output_token_account.reload()?;
+    let amount_in = input_before.saturating_sub(ctx.accounts.user_input_token_account.amount);
+    let amount_out = ctx
+        .accounts
+        .user_output_token_account
+        .amount
+        .saturating_sub(output_before);
+    require_gte!(
+        amount_out,
+        min_amount_out,
+        CpiSwapError::MinimumOutputNotMet
+    );
+
+    emit_cpi!(UserSwapEvent {
+        user: ctx.accounts.user.key(),
+        input_mint: ctx.accounts.input_mint.key(),
+        output_mint: ctx.accounts.output_mint.key(),
+        amount_in,
+        amount_out,
+    });
+
+    Ok(SwapResult {
+        amount_in,
+        amount_out,
+        input_mint: ctx.accounts.input_mint.key(),
+        output_mint: ctx.accounts.output_mint.key(),
+    })
+}
@@ -0,0 +1,199 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program_option::COption, sysvar},
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    aggregator::VaultRoute,
+    constants::{
+        AGGREGATOR_REGISTRY_SEED, ROUTE_POLICY_SEED, STRATEGY_SEED, USER_NONCE_SEED, VAULT_SEED,
+    },
+    errors::CpiSwapError,
+    events::IntentSwapEvent,
+    state::{AggregatorRegistry, RoutePolicy, StrategyConfig, SwapIntent, UserNonce},
+    utils::verify_ed25519_instruction,
+    SwapResult,
+};
+
+/// Executes a [`SwapIntent`] signed by `user`. The transaction must carry an
+/// ed25519 precompile instruction over `intent.message()` immediately before
+/// this one; the relayer only pays fees and picks the route.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(intent: SwapIntent)]
+pub struct SwapWithIntent<'info> {
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: must be the intent signer; verified against the ed25519 instruction
+    #[account(address = intent.user @ CpiSwapError::IntentMismatch)]
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+      init_if_needed,
+      payer = relayer,
+      space = 8 + UserNonce::INIT_SPACE,
+      seeds = [USER_NONCE_SEED, user.key().as_ref()],
+      bump
+    )]
+    pub user_nonce: Account<'info, UserNonce>,
+
+    #[account(
+      address = intent.input_mint @ CpiSwapError::IntentMismatch,
+      constraint = strategy.allows_mint(&input_mint.key()) @ CpiSwapError::MintNotAllowed,
+    )]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+    pub input_mint_program: Interface<'info, TokenInterface>,
+    #[account(
+      address = intent.output_mint @ CpiSwapError::IntentMismatch,
+      constraint = strategy.allows_mint(&output_mint.key()) @ CpiSwapError::MintNotAllowed,
+    )]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+    pub output_mint_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &intent.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      token::mint = input_mint,
+      token::authority = user,
+      token::token_program = input_mint_program,
+      constraint = user_input_token_account.delegate == COption::Some(vault.key())
+        @ CpiSwapError::VaultNotDelegate,
+    )]
+    pub user_input_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+      mut,
+      associated_token::mint = output_mint,
+      associated_token::authority = user,
+      associated_token::token_program = output_mint_program,
+    )]
+    pub user_output_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+      seeds = [AGGREGATOR_REGISTRY_SEED],
+      bump = aggregator_registry.bump,
+    )]
+    pub aggregator_registry: Account<'info, AggregatorRegistry>,
+
+    #[account(
+      seeds = [ROUTE_POLICY_SEED, vault.key().as_ref()],
+      bump = route_policy.bump,
+    )]
+    pub route_policy: Account<'info, RoutePolicy>,
+
+    /// CHECK: checked against `aggregator_registry` before invoking
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+
+    /// CHECK: the instructions sysvar
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SwapWithIntent<'info> {
+    /// Verifies the user's signature over `intent`, rejects expired intents and
+    /// consumes the user's current nonce.
+    pub fn consume_intent(&mut self, intent: &SwapIntent, nonce_bump: u8) -> Result<()> {
+        verify_ed25519_instruction(&self.instructions, self.user.key, &intent.message()?)?;
+
+        require_gt!(
+            intent.expiry,
+            Clock::get()?.unix_timestamp,
+            CpiSwapError::IntentExpired
+        );
+
+        if self.user_nonce.user == Pubkey::default() {
+            self.user_nonce.user = self.user.key();
+            self.user_nonce.bump = nonce_bump;
+        }
+        require_eq!(
+            intent.nonce,
+            self.user_nonce.nonce,
+            CpiSwapError::InvalidIntentNonce
+        );
+        self.user_nonce.nonce = self
+            .user_nonce
+            .nonce
+            .checked_add(1)
+            .ok_or(CpiSwapError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn invoke_jupiter(&self, remaining_accounts: &[AccountInfo], data: Vec<u8>) -> Result<()> {
+        VaultRoute {
+            aggregator_registry: &self.aggregator_registry,
+            route_policy: &self.route_policy,
+            strategy: &self.strategy,
+            aggregator_program: self.aggregator_program.key(),
+            vault: self.vault.to_account_info(),
+            writable: &[self.user_input_token_account.key()],
+            destination: self.user_output_token_account.key(),
+            platform_fee: None,
+        }
+        .invoke(remaining_accounts, data)?;
+
+        Ok(())
+    }
+}
+
+pub fn handle_swap_with_intent(
+    ctx: Context<SwapWithIntent>,
+    intent: SwapIntent,
+    data: Vec<u8>,
+) -> Result<SwapResult> {
+    ctx.accounts.consume_intent(&intent, ctx.bumps.user_nonce)?;
+
+    let input_before = ctx.accounts.user_input_token_account.amount;
+    let output_before = ctx.accounts.user_output_token_account.amount;
+
+    ctx.accounts.invoke_jupiter(ctx.remaining_accounts, data)?;
+
+    ctx.accounts.user_input_token_account.reload()?;
+    ctx.accounts.user_output_token_account.reload()?;
+    let amount_in = input_before.saturating_sub(ctx.accounts.user_input_token_account.amount);
+    let amount_out = ctx
+        .accounts
+        .user_output_token_account
+        .amount
+        .saturating_sub(output_before);
+
+    require_gte!(intent.amount_in, amount_in, CpiSwapError::IntentMismatch);
+    require_gte!(
+        amount_out,
+        intent.min_amount_out,
+        CpiSwapError::MinimumOutputNotMet
+    );
+
+    emit_cpi!(IntentSwapEvent {
+        relayer: ctx.accounts.relayer.key(),
+        user: intent.user,
+        input_mint: intent.input_mint,
+        output_mint: intent.output_mint,
+        amount_in,
+        amount_out,
+        nonce: intent.nonce,
+    });
+
+    Ok(SwapResult {
+        amount_in,
+        amount_out,
+        input_mint: intent.input_mint,
+        output_mint: intent.output_mint,
+    })
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::CpiSwapError,
+    events::{HookInvokedEvent, SwapEvent},
+    settle_swap, Swap, SwapResult,
+};
+
+/// Swaps like `swap`, then hands the output to the configured hook program in
+/// the same transaction. The last `hook_account_count` remaining accounts are
+/// the hook's accounts; the rest belong to the Jupiter route. Managers may
+/// call it like `swap`: the hook can only write to the vault, its output
+/// token account and the accounts pinned for it in the hook registry.
+pub fn handle_swap_with_hook(
+    mut ctx: Context<Swap>,
+    data: Vec<u8>,
+    hook_data: Vec<u8>,
+    hook_account_count: u8,
+) -> Result<SwapResult> {
+    require!(
+        ctx.accounts.recipient_token_account.is_none(),
+        CpiSwapError::HookWithRecipient
+    );
+    let hook_program = ctx
+        .accounts
+        .hook_program
+        .as_ref()
+        .ok_or(CpiSwapError::MissingHookAccounts)?
+        .key();
+
+    let split = ctx
+        .remaining_accounts
+        .len()
+        .checked_sub(hook_account_count as usize)
+        .ok_or(CpiSwapError::MissingHookAccounts)?;
+    let (route_accounts, hook_accounts) = ctx.remaining_accounts.split_at(split);
+
+    let before = ctx.accounts.balances();
+    let min_amount_out = ctx.accounts.invoke_jupiter(route_accounts, data)?;
+    let (amount_in, amount_out) = settle_swap(&mut ctx, before, min_amount_out)?;
+
+    let hook_spent = ctx
+        .accounts
+        .invoke_hook(hook_accounts, hook_data, amount_out)?;
+
+    emit_cpi!(SwapEvent {
+        operator: ctx.accounts.operator.key(),
+        input_mint: ctx.accounts.input_mint.key(),
+        output_mint: ctx.accounts.output_mint.key(),
+        amount_in,
+        amount_out,
+    });
+
+    emit_cpi!(HookInvokedEvent {
+        hook_program,
+        mint: ctx.accounts.output_mint.key(),
+        amount: hook_spent,
+    });
+
+    Ok(SwapResult {
+        amount_in,
+        amount_out,
+        input_mint: ctx.accounts.input_mint.key(),
+        output_mint: ctx.accounts.output_mint.key(),
+    })
+}
@@ -0,0 +1,258 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_2022::Token2022,
+    token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    aggregator::VaultRoute,
+    constants::{
+        AGGREGATOR_REGISTRY_SEED, CONFIG_SEED, ROUTE_POLICY_SEED, STRATEGY_SEED, VAULT_SEED,
+        VAULT_STATS_SEED,
+    },
+    errors::CpiSwapError,
+    events::SweepEvent,
+    state::{AggregatorRegistry, RoutePolicy, StrategyConfig, VaultConfig, VaultStats},
+    utils::unpack_token_account,
+};
+
+/// One vault token account to sweep. In the remaining accounts it is laid out
+/// as the vault token account followed by `account_count` route accounts.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SweepRoute {
+    pub data: Vec<u8>,
+    pub account_count: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SweepResult {
+    pub swept: u8,
+    pub skipped: u8,
+    pub closed: u8,
+    pub amount_out: u64,
+}
+
+/// Consolidates vault balances into `output_mint`. Sweeps are not recorded
+/// against volume limits, so only the admin may run them.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(constraint = strategy.allows_mint(&output_mint.key()) @ CpiSwapError::MintNotAllowed)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+    pub output_mint_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      mut,
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      associated_token::mint = output_mint,
+      associated_token::authority = vault,
+      associated_token::token_program = output_mint_program,
+    )]
+    pub vault_output_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+      seeds = [AGGREGATOR_REGISTRY_SEED],
+      bump = aggregator_registry.bump,
+    )]
+    pub aggregator_registry: Account<'info, AggregatorRegistry>,
+
+    #[account(
+      seeds = [ROUTE_POLICY_SEED, vault.key().as_ref()],
+      bump = route_policy.bump,
+    )]
+    pub route_policy: Account<'info, RoutePolicy>,
+
+    #[account(
+      init_if_needed,
+      payer = admin,
+      space = 8 + VaultStats::INIT_SPACE,
+      seeds = [VAULT_STATS_SEED, vault.key().as_ref()],
+      bump
+    )]
+    pub vault_stats: Account<'info, VaultStats>,
+
+    /// CHECK: checked against `aggregator_registry` before invoking
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Sweep<'info> {
+    /// Swaps the whole balance of one vault token account through `route`,
+    /// failing if it receives less than the route's own minimum output.
+    /// Returns the balance left in the source account and the output received.
+    pub fn sweep_one(
+        &self,
+        source: &AccountInfo<'info>,
+        route_accounts: &[AccountInfo],
+        data: Vec<u8>,
+    ) -> Result<(u64, u64)> {
+        let output_token_account = self.vault_output_token_account.to_account_info();
+        let output_balance = || -> Result<u64> {
+            Ok(unpack_token_account(&output_token_account)
+                .ok_or(CpiSwapError::InvalidSweepAccount)?
+                .amount)
+        };
+        let output_before = output_balance()?;
+
+        let min_amount_out = VaultRoute {
+            aggregator_registry: &self.aggregator_registry,
+            route_policy: &self.route_policy,
+            strategy: &self.strategy,
+            aggregator_program: self.aggregator_program.key(),
+            vault: self.vault.to_account_info(),
+            writable: &[source.key(), self.vault_output_token_account.key()],
+            destination: self.vault_output_token_account.key(),
+            platform_fee: None,
+        }
+        .invoke(route_accounts, data)?;
+
+        let source_token_account =
+            unpack_token_account(source).ok_or(CpiSwapError::InvalidSweepAccount)?;
+        let amount_out = output_balance()?.saturating_sub(output_before);
+        require_gte!(
+            amount_out,
+            min_amount_out,
+            CpiSwapError::MinimumOutputNotMet
+        );
+
+        Ok((source_token_account.amount, amount_out))
+    }
+
+    /// Closes an empty vault token account and returns its rent to the vault.
+    pub fn close(&self, source: &AccountInfo<'info>) -> Result<()> {
+        let token_program = if source.owner == self.token_2022_program.key {
+            self.token_2022_program.to_account_info()
+        } else {
+            self.token_program.to_account_info()
+        };
+
+        let strategy_id = self.strategy.strategy_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &strategy_id, &[self.strategy.vault_bump]]];
+        close_account(CpiContext::new_with_signer(
+            token_program,
+            CloseAccount {
+                account: source.clone(),
+                destination: self.vault.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            signer_seeds,
+        ))
+    }
+}
+
+pub fn handle_sweep<'info>(
+    ctx: Context<'_, '_, '_, 'info, Sweep<'info>>,
+    routes: Vec<SweepRoute>,
+    dust_threshold: u64,
+    close_empty: bool,
+) -> Result<SweepResult> {
+    let vault = ctx.accounts.vault.key();
+    let output_mint = ctx.accounts.output_mint.key();
+    let output_before = ctx.accounts.vault_output_token_account.amount;
+
+    let slot = Clock::get()?.slot;
+    ctx.accounts
+        .vault_stats
+        .init_if_needed(vault, ctx.bumps.vault_stats);
+
+    let mut result = SweepResult {
+        swept: 0,
+        skipped: 0,
+        closed: 0,
+        amount_out: 0,
+    };
+
+    let mut remaining_accounts = ctx.remaining_accounts;
+    for route in routes {
+        let (source, rest) = remaining_accounts
+            .split_first()
+            .ok_or(CpiSwapError::MissingSweepAccounts)?;
+        require_gte!(
+            rest.len(),
+            route.account_count as usize,
+            CpiSwapError::MissingSweepAccounts
+        );
+        let (route_accounts, rest) = rest.split_at(route.account_count as usize);
+        remaining_accounts = rest;
+
+        let source_token_account =
+            unpack_token_account(source).ok_or(CpiSwapError::InvalidSweepAccount)?;
+        require_keys_eq!(
+            source_token_account.owner,
+            vault,
+            CpiSwapError::InvalidSweepAccount
+        );
+        require_keys_neq!(
+            source_token_account.mint,
+            output_mint,
+            CpiSwapError::InvalidSweepAccount
+        );
+
+        let mut balance = source_token_account.amount;
+        if balance > 0 && balance < dust_threshold {
+            result.skipped += 1;
+            continue;
+        }
+        if balance > 0 {
+            let (remaining, amount_out) =
+                ctx.accounts.sweep_one(source, route_accounts, route.data)?;
+            ctx.accounts.vault_stats.record_swap(
+                source_token_account.mint,
+                output_mint,
+                balance.saturating_sub(remaining),
+                amount_out,
+                slot,
+            )?;
+            balance = remaining;
+            result.swept += 1;
+        }
+        if close_empty && balance == 0 {
+            ctx.accounts.close(source)?;
+            result.closed += 1;
+        }
+    }
+
+    ctx.accounts.vault_output_token_account.reload()?;
+    result.amount_out = ctx
+        .accounts
+        .vault_output_token_account
+        .amount
+        .saturating_sub(output_before);
+
+    emit_cpi!(SweepEvent {
+        output_mint,
+        swept: result.swept,
+        skipped: result.skipped,
+        closed: result.closed,
+        amount_out: result.amount_out,
+    });
+
+    Ok(result)
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    constants::{CONFIG_SEED, STRATEGY_SEED, VAULT_SEED},
+    errors::CpiSwapError,
+    events::WithdrawEvent,
+    state::{StrategyConfig, VaultConfig},
+};
+
+/// Moves tokens out of a strategy vault. Only the config admin can withdraw;
+/// managers and keepers have no path to move funds out of the vault.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+      seeds = [CONFIG_SEED],
+      bump = config.bump,
+      has_one = admin @ CpiSwapError::Unauthorized,
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(
+      seeds = [STRATEGY_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    #[account(
+      seeds = [VAULT_SEED, &strategy.strategy_id.to_le_bytes()],
+      bump = strategy.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      token::mint = mint,
+      token::authority = vault,
+      token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+      mut,
+      token::mint = mint,
+      token::token_program = token_program,
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+pub fn handle_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let strategy_id = accounts.strategy.strategy_id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &strategy_id, &[accounts.strategy.vault_bump]]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: accounts.vault_token_account.to_account_info(),
+                mint: accounts.mint.to_account_info(),
+                to: accounts.destination_token_account.to_account_info(),
+                authority: accounts.vault.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        accounts.mint.decimals,
+    )?;
+
+    emit_cpi!(WithdrawEvent {
+        strategy_id: accounts.strategy.strategy_id,
+        mint: accounts.mint.key(),
+        destination_token_account: accounts.destination_token_account.key(),
+        amount,
+    });
+
+    Ok(())
+}
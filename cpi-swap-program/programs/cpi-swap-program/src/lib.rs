@@ -1,91 +1,257 @@
-use anchor_lang::{
-    prelude::*,
-    solana_program::{instruction::Instruction, program::invoke_signed},
-};
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-use jupiter_aggregator::program::Jupiter;
-use std::str::FromStr;
+use anchor_lang::prelude::*;
 
-declare_program!(jupiter_aggregator);
-declare_id!("8KQG1MYXru73rqobftpFjD3hBD8Ab3jaag8wbjZG63sx");
+pub mod aggregator;
+pub mod constants;
+#[cfg(feature = "cpi")]
+pub mod cpi_helpers;
+pub mod errors;
+pub mod events;
+pub mod instructions;
+pub mod oracle;
+#[cfg(feature = "cpi")]
+pub mod return_data;
+pub mod route_plan;
+pub mod state;
+pub mod utils;
 
-const VAULT_SEED: &[u8] = b"vault";
+pub use constants::*;
+pub use instructions::*;
+pub use state::*;
 
-pub fn jupiter_program_id() -> Pubkey {
-    Pubkey::from_str("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4").unwrap()
-}
+declare_program!(jupiter_aggregator);
+declare_id!("8KQG1MYXru73rqobftpFjD3hBD8Ab3jaag8wbjZG63sx");
 
 #[program]
 pub mod cpi_swap_program {
     use super::*;
 
-    pub fn swap(ctx: Context<Swap>, data: Vec<u8>) -> Result<()> {
-        require_keys_eq!(*ctx.accounts.jupiter_program.key, jupiter_program_id());
-
-        let accounts: Vec<AccountMeta> = ctx
-            .remaining_accounts
-            .iter()
-            .map(|acc| {
-                let is_signer = acc.key == &ctx.accounts.vault.key();
-                AccountMeta {
-                    pubkey: *acc.key,
-                    is_signer,
-                    is_writable: acc.is_writable,
-                }
-            })
-            .collect();
-
-        let accounts_infos: Vec<AccountInfo> = ctx
-            .remaining_accounts
-            .iter()
-            .map(|acc| AccountInfo { ..acc.clone() })
-            .collect();
-
-        let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.vault]]];
-
-        invoke_signed(
-            &Instruction {
-                program_id: ctx.accounts.jupiter_program.key(),
-                accounts,
-                data,
-            },
-            &accounts_infos,
-            signer_seeds,
-        )?;
-
-        Ok(())
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        handle_initialize_config(ctx)
+    }
+
+    pub fn set_strategy(
+        ctx: Context<SetStrategy>,
+        strategy_id: u64,
+        allowed_mints: Vec<Pubkey>,
+        recipients: Vec<Pubkey>,
+        max_slippage_bps: u16,
+        platform_fee_bps: u8,
+    ) -> Result<()> {
+        handle_set_strategy(
+            ctx,
+            strategy_id,
+            allowed_mints,
+            recipients,
+            max_slippage_bps,
+            platform_fee_bps,
+        )
+    }
+
+    pub fn set_volume_limit(
+        ctx: Context<SetVolumeLimit>,
+        window_seconds: i64,
+        max_vault_volume: u64,
+        max_operator_volume: u64,
+    ) -> Result<()> {
+        handle_set_volume_limit(ctx, window_seconds, max_vault_volume, max_operator_volume)
+    }
+
+    pub fn set_aggregator(
+        ctx: Context<SetAggregator>,
+        program_id: Pubkey,
+        decoder: RouteDecoder,
+        discriminators: Vec<[u8; 8]>,
+    ) -> Result<()> {
+        handle_set_aggregator(ctx, program_id, decoder, discriminators)
+    }
+
+    pub fn remove_aggregator(ctx: Context<RemoveAggregator>, program_id: Pubkey) -> Result<()> {
+        handle_remove_aggregator(ctx, program_id)
+    }
+
+    pub fn set_referral_authority(
+        ctx: Context<SetReferralAuthority>,
+        referral_authority: Pubkey,
+    ) -> Result<()> {
+        handle_set_referral_authority(ctx, referral_authority)
+    }
+
+    pub fn set_route_policy(
+        ctx: Context<SetRoutePolicy>,
+        max_hops: u8,
+        max_slippage_bps: u16,
+        venue_filter: VenueFilter,
+        venues: Vec<u8>,
+        allow_undecoded_routes: bool,
+    ) -> Result<()> {
+        handle_set_route_policy(
+            ctx,
+            max_hops,
+            max_slippage_bps,
+            venue_filter,
+            venues,
+            allow_undecoded_routes,
+        )
+    }
+
+    pub fn swap(ctx: Context<Swap>, data: Vec<u8>) -> Result<SwapResult> {
+        handle_swap(ctx, data)
+    }
+
+    pub fn swap_delegated(
+        ctx: Context<SwapDelegated>,
+        data: Vec<u8>,
+        min_amount_out: u64,
+    ) -> Result<SwapResult> {
+        handle_swap_delegated(ctx, data, min_amount_out)
+    }
+
+    pub fn swap_exact_out(
+        ctx: Context<Swap>,
+        data: Vec<u8>,
+        out_amount: u64,
+        max_in: u64,
+    ) -> Result<SwapExactOutResult> {
+        handle_swap_exact_out(ctx, data, out_amount, max_in)
+    }
+
+    pub fn swap_from_user(
+        ctx: Context<SwapFromUser>,
+        data: Vec<u8>,
+        min_amount_out: u64,
+    ) -> Result<SwapResult> {
+        handle_swap_from_user(ctx, data, min_amount_out)
+    }
+
+    pub fn set_delegation_terms(ctx: Context<SetDelegationTerms>, min_price: u64) -> Result<()> {
+        handle_set_delegation_terms(ctx, min_price)
+    }
+
+    pub fn swap_with_intent(
+        ctx: Context<SwapWithIntent>,
+        intent: SwapIntent,
+        data: Vec<u8>,
+    ) -> Result<SwapResult> {
+        handle_swap_with_intent(ctx, intent, data)
+    }
+
+    pub fn set_hook(
+        ctx: Context<SetHook>,
+        program_id: Pubkey,
+        discriminators: Vec<[u8; 8]>,
+        writable_accounts: Vec<Pubkey>,
+    ) -> Result<()> {
+        handle_set_hook(ctx, program_id, discriminators, writable_accounts)
+    }
+
+    pub fn remove_hook(ctx: Context<RemoveHook>, program_id: Pubkey) -> Result<()> {
+        handle_remove_hook(ctx, program_id)
     }
-}
 
-#[derive(Accounts)]
-pub struct Swap<'info> {
-    pub input_mint: InterfaceAccount<'info, Mint>,
-    pub input_mint_program: Interface<'info, TokenInterface>,
-    pub output_mint: InterfaceAccount<'info, Mint>,
-    pub output_mint_program: Interface<'info, TokenInterface>,
-
-    #[account(
-      mut,
-      seeds=[VAULT_SEED],
-      bump
-    )]
-    pub vault: SystemAccount<'info>,
-
-    #[account(
-      mut,
-      associated_token::mint=input_mint,
-      associated_token::authority=vault,
-      associated_token::token_program=input_mint_program,
-    )]
-    pub vault_input_token_account: InterfaceAccount<'info, TokenAccount>,
-
-    #[account(
-      mut,
-      associated_token::mint=output_mint,
-      associated_token::authority=vault,
-      associated_token::token_program=output_mint_program,
-    )]
-    pub vault_output_token_account: InterfaceAccount<'info, TokenAccount>,
-
-    pub jupiter_program: Program<'info, Jupiter>,
+    pub fn swap_with_hook(
+        ctx: Context<Swap>,
+        data: Vec<u8>,
+        hook_data: Vec<u8>,
+        hook_account_count: u8,
+    ) -> Result<SwapResult> {
+        handle_swap_with_hook(ctx, data, hook_data, hook_account_count)
+    }
+
+    pub fn sweep<'info>(
+        ctx: Context<'_, '_, '_, 'info, Sweep<'info>>,
+        routes: Vec<SweepRoute>,
+        dust_threshold: u64,
+        close_empty: bool,
+    ) -> Result<SweepResult> {
+        handle_sweep(ctx, routes, dust_threshold, close_empty)
+    }
+
+    pub fn close_vault_token_account(
+        ctx: Context<CloseVaultTokenAccount>,
+        remainder: CloseRemainder,
+    ) -> Result<()> {
+        handle_close_vault_token_account(ctx, remainder)
+    }
+
+    pub fn get_vault_state(ctx: Context<GetVaultState>) -> Result<VaultStats> {
+        handle_get_vault_state(ctx)
+    }
+
+    pub fn set_strategy_roles(
+        ctx: Context<SetStrategyRoles>,
+        managers: Vec<Pubkey>,
+        keepers: Vec<Pubkey>,
+    ) -> Result<()> {
+        handle_set_strategy_roles(ctx, managers, keepers)
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        handle_withdraw(ctx, amount)
+    }
+
+    pub fn migrate_legacy_vault(ctx: Context<MigrateLegacyVault>) -> Result<()> {
+        handle_migrate_legacy_vault(ctx)
+    }
+
+    pub fn set_fee_reimbursement(
+        ctx: Context<SetFeeReimbursement>,
+        lamports_per_swap: u64,
+        max_lamports_per_day: u64,
+    ) -> Result<()> {
+        handle_set_fee_reimbursement(ctx, lamports_per_swap, max_lamports_per_day)
+    }
+
+    pub fn set_keeper_reward_limits(
+        ctx: Context<SetKeeperRewardLimits>,
+        max_keeper_reward: u64,
+        max_order_keeper_rewards: u64,
+    ) -> Result<()> {
+        handle_set_keeper_reward_limits(ctx, max_keeper_reward, max_order_keeper_rewards)
+    }
+
+    pub fn create_twap_order(
+        ctx: Context<CreateTwapOrder>,
+        order_id: u64,
+        params: TwapOrderParams,
+    ) -> Result<()> {
+        handle_create_twap_order(ctx, order_id, params)
+    }
+
+    pub fn execute_twap_slice(ctx: Context<ExecuteTwapSlice>, data: Vec<u8>) -> Result<SwapResult> {
+        handle_execute_twap_slice(ctx, data)
+    }
+
+    pub fn close_twap_order(ctx: Context<CloseTwapOrder>) -> Result<()> {
+        handle_close_twap_order(ctx)
+    }
+
+    pub fn set_vault_fees(
+        ctx: Context<SetVaultFees>,
+        fee_recipient: Pubkey,
+        management_fee_bps: u16,
+        performance_fee_bps: u16,
+    ) -> Result<()> {
+        handle_set_vault_fees(ctx, fee_recipient, management_fee_bps, performance_fee_bps)
+    }
+
+    pub fn issue_vault_shares(ctx: Context<IssueVaultShares>, amount: u64) -> Result<()> {
+        handle_issue_vault_shares(ctx, amount)
+    }
+
+    pub fn accrue_vault_fees(ctx: Context<AccrueVaultFees>) -> Result<()> {
+        handle_accrue_vault_fees(ctx)
+    }
+
+    pub fn set_nav_assets(
+        ctx: Context<SetNavAssets>,
+        assets: Vec<NavAssetOracle>,
+        max_price_age: u64,
+        max_confidence_bps: u16,
+    ) -> Result<()> {
+        handle_set_nav_assets(ctx, assets, max_price_age, max_confidence_bps)
+    }
+
+    pub fn compute_nav(ctx: Context<ComputeNav>) -> Result<NavResult> {
+        handle_compute_nav(ctx)
+    }
 }
@@ -0,0 +1,179 @@
+use anchor_lang::{prelude::*, pubkey};
+
+use crate::errors::CpiSwapError;
+
+/// Pyth pull oracle receiver, owner of `PriceUpdateV2` accounts.
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+/// Borsh index of `VerificationLevel::Full`; partially verified updates are
+/// rejected.
+const VERIFICATION_LEVEL_FULL: u8 = 1;
+/// Offset of the price message in a fully verified update, after the
+/// discriminator, write authority and verification level.
+const PRICE_MESSAGE_OFFSET: usize = 8 + 32 + 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OraclePrice {
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    /// Reads a fully verified Pyth `PriceUpdateV2` account for `feed_id`. The
+    /// layout is parsed by hand to avoid pulling in the receiver SDK.
+    pub fn from_pyth(account: &AccountInfo, feed_id: &[u8; 32]) -> Result<Self> {
+        require_keys_eq!(
+            *account.owner,
+            PYTH_RECEIVER_PROGRAM_ID,
+            CpiSwapError::InvalidOracleAccount
+        );
+        Self::from_price_update(&account.try_borrow_data()?, feed_id)
+    }
+
+    fn from_price_update(data: &[u8], feed_id: &[u8; 32]) -> Result<Self> {
+        require!(
+            data.len() >= PRICE_MESSAGE_OFFSET + 60
+                && data[..8] == PRICE_UPDATE_V2_DISCRIMINATOR
+                && data[40] == VERIFICATION_LEVEL_FULL,
+            CpiSwapError::InvalidOracleAccount
+        );
+
+        // The price message starts with the 32 byte feed id
+        let (message_feed_id, message) = data[PRICE_MESSAGE_OFFSET..].split_at(32);
+        require!(message_feed_id == feed_id, CpiSwapError::InvalidOracleFeed);
+        let read_u64 = |at: usize| u64::from_le_bytes(message[at..at + 8].try_into().unwrap());
+        Ok(Self {
+            price: read_u64(0) as i64,
+            conf: read_u64(8),
+            exponent: i32::from_le_bytes(message[16..20].try_into().unwrap()),
+            publish_time: read_u64(20) as i64,
+        })
+    }
+
+    /// Fails if the confidence interval is wider than `max_confidence_bps` of
+    /// the price.
+    pub fn check_confidence(&self, max_confidence_bps: u16) -> Result<()> {
+        require_gt!(self.price, 0, CpiSwapError::InvalidOraclePrice);
+        require_gte!(
+            self.price as u128 * u128::from(max_confidence_bps),
+            u128::from(self.conf) * 10_000,
+            CpiSwapError::OraclePriceUncertain
+        );
+        Ok(())
+    }
+
+    /// Values `amount` raw units of a token with `decimals` in quote units with
+    /// `quote_decimals`, rounding down.
+    pub fn value(&self, amount: u64, decimals: u8, quote_decimals: u8) -> Result<u64> {
+        require_gt!(self.price, 0, CpiSwapError::InvalidOraclePrice);
+
+        let value = u128::from(amount) * self.price as u128;
+        let scale = i32::from(quote_decimals) + self.exponent - i32::from(decimals);
+        let factor = 10u128
+            .checked_pow(scale.unsigned_abs())
+            .ok_or(CpiSwapError::MathOverflow)?;
+        let value = match scale >= 0 {
+            true => value
+                .checked_mul(factor)
+                .ok_or(CpiSwapError::MathOverflow)?,
+            false => value / factor,
+        };
+
+        u64::try_from(value).map_err(|_| error!(CpiSwapError::MathOverflow))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED_ID: [u8; 32] = [7; 32];
+
+    fn price(price: i64, exponent: i32) -> OraclePrice {
+        OraclePrice {
+            price,
+            conf: 0,
+            exponent,
+            publish_time: 0,
+        }
+    }
+
+    fn price_update(price: i64, conf: u64, exponent: i32, publish_time: i64) -> Vec<u8> {
+        let mut data = PRICE_UPDATE_V2_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[0; 32]);
+        data.push(VERIFICATION_LEVEL_FULL);
+        data.extend_from_slice(&FEED_ID);
+        data.extend_from_slice(&price.to_le_bytes());
+        data.extend_from_slice(&conf.to_le_bytes());
+        data.extend_from_slice(&exponent.to_le_bytes());
+        data.extend_from_slice(&publish_time.to_le_bytes());
+        // Previous publish time, EMA price and EMA confidence
+        data.extend_from_slice(&[0; 24]);
+        data
+    }
+
+    #[test]
+    fn from_price_update_reads_the_message() {
+        let data = price_update(150_000_000, 75_000, -8, 1_700_000_000);
+        assert_eq!(
+            OraclePrice::from_price_update(&data, &FEED_ID).unwrap(),
+            OraclePrice {
+                price: 150_000_000,
+                conf: 75_000,
+                exponent: -8,
+                publish_time: 1_700_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn from_price_update_rejects_other_feeds_and_partial_updates() {
+        let data = price_update(150_000_000, 0, -8, 0);
+        assert!(OraclePrice::from_price_update(&data, &[8; 32]).is_err());
+
+        let mut partial = data.clone();
+        partial[40] = 0;
+        assert!(OraclePrice::from_price_update(&partial, &FEED_ID).is_err());
+        assert!(OraclePrice::from_price_update(&data[..data.len() - 25], &FEED_ID).is_err());
+    }
+
+    #[test]
+    fn check_confidence_bounds_the_interval() {
+        let mut oracle_price = price(10_000, -2);
+        oracle_price.conf = 100;
+        oracle_price.check_confidence(100).unwrap();
+        assert!(oracle_price.check_confidence(99).is_err());
+    }
+
+    #[test]
+    fn value_scales_down_to_the_quote_decimals() {
+        // 1.5 SOL at $150.00000000 is $225.000000
+        let sol = price(15_000_000_000, -8);
+        assert_eq!(sol.value(1_500_000_000, 9, 6).unwrap(), 225_000_000);
+        // Rounds down
+        assert_eq!(sol.value(1, 9, 6).unwrap(), 0);
+    }
+
+    #[test]
+    fn value_scales_up_to_the_quote_decimals() {
+        // 2 units of a token with no decimals at 3 * 10^2
+        let oracle_price = price(3, 2);
+        assert_eq!(oracle_price.value(2, 0, 6).unwrap(), 600_000_000);
+    }
+
+    #[test]
+    fn value_fails_on_overflow() {
+        assert!(price(i64::MAX, 0).value(u64::MAX, 0, 6).is_err());
+        // The scale factor itself overflows
+        assert!(price(1, 40).value(1, 0, 6).is_err());
+    }
+
+    #[test]
+    fn value_rejects_non_positive_prices() {
+        assert!(price(0, -8).value(1, 9, 6).is_err());
+        assert!(price(-1, -8).value(1, 9, 6).is_err());
+    }
+}
@@ -0,0 +1,32 @@
+//! Readers for the results `swap`, `swap_exact_out`, `get_vault_state` and
+//! `compute_nav` publish through `set_return_data`, for programs that invoke
+//! the vault without going through the generated `cpi::Return` wrapper.
+
+use anchor_lang::{prelude::*, solana_program::program::get_return_data};
+
+use crate::{state::VaultStats, NavResult, SwapExactOutResult, SwapResult};
+
+/// Deserializes the current return data, provided it was set by this program.
+pub fn read_return_data<T: AnchorDeserialize>() -> Option<T> {
+    let (program_id, data) = get_return_data()?;
+    if program_id != crate::ID {
+        return None;
+    }
+    T::try_from_slice(&data).ok()
+}
+
+pub fn read_swap_result() -> Option<SwapResult> {
+    read_return_data()
+}
+
+pub fn read_swap_exact_out_result() -> Option<SwapExactOutResult> {
+    read_return_data()
+}
+
+pub fn read_vault_state() -> Option<VaultStats> {
+    read_return_data()
+}
+
+pub fn read_nav_result() -> Option<NavResult> {
+    read_return_data()
+}
@@ -0,0 +1,315 @@
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::{
+    errors::CpiSwapError,
+    jupiter_aggregator::{
+        client::args::{
+            ExactOutRoute, Route, RouteWithTokenLedger, SharedAccountsExactOutRoute,
+            SharedAccountsRoute, SharedAccountsRouteWithTokenLedger,
+        },
+        types::RoutePlanStep,
+    },
+};
+
+/// The policy-relevant arguments of any Jupiter v6 route instruction.
+pub struct DecodedRoute {
+    pub route_plan: Vec<RoutePlanStep>,
+    pub slippage_bps: u16,
+    pub platform_fee_bps: u8,
+    /// Least output the route itself accepts: the exact `out_amount` of an
+    /// exact-out route, otherwise the quote less the slippage tolerance.
+    pub min_amount_out: u64,
+    /// Position of `user_transfer_authority` in the instruction's account list.
+    pub user_transfer_authority_index: usize,
+    /// Position of `platform_fee_account` in the instruction's account list.
+    pub platform_fee_account_index: usize,
+    /// Position of the token account the output is delivered to.
+    pub destination_account_index: usize,
+    /// Position of the optional `destination_token_account` of non-shared
+    /// routes, which takes the output instead when it is passed.
+    pub destination_override_index: Option<usize>,
+    /// Exact-out routes charge the platform fee in the input mint, others in
+    /// the output mint.
+    pub exact_out: bool,
+}
+
+impl DecodedRoute {
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let discriminator = data.get(..8).ok_or(CpiSwapError::UndecodedRoute)?;
+        let args = &data[8..];
+
+        let (
+            route_plan,
+            slippage_bps,
+            platform_fee_bps,
+            min_amount_out,
+            user_transfer_authority_index,
+            platform_fee_account_index,
+            destination_account_index,
+            destination_override_index,
+        ) = if discriminator == Route::DISCRIMINATOR {
+            let ix = Route::try_from_slice(args)?;
+            (
+                ix.route_plan,
+                ix.slippage_bps,
+                ix.platform_fee_bps,
+                min_out(ix.quoted_out_amount, ix.slippage_bps),
+                1,
+                6,
+                3,
+                Some(4),
+            )
+        } else if discriminator == RouteWithTokenLedger::DISCRIMINATOR {
+            let ix = RouteWithTokenLedger::try_from_slice(args)?;
+            (
+                ix.route_plan,
+                ix.slippage_bps,
+                ix.platform_fee_bps,
+                min_out(ix.quoted_out_amount, ix.slippage_bps),
+                1,
+                6,
+                3,
+                Some(4),
+            )
+        } else if discriminator == ExactOutRoute::DISCRIMINATOR {
+            let ix = ExactOutRoute::try_from_slice(args)?;
+            (
+                ix.route_plan,
+                ix.slippage_bps,
+                ix.platform_fee_bps,
+                ix.out_amount,
+                1,
+                7,
+                3,
+                Some(4),
+            )
+        } else if discriminator == SharedAccountsRoute::DISCRIMINATOR {
+            let ix = SharedAccountsRoute::try_from_slice(args)?;
+            (
+                ix.route_plan,
+                ix.slippage_bps,
+                ix.platform_fee_bps,
+                min_out(ix.quoted_out_amount, ix.slippage_bps),
+                2,
+                9,
+                6,
+                None,
+            )
+        } else if discriminator == SharedAccountsRouteWithTokenLedger::DISCRIMINATOR {
+            let ix = SharedAccountsRouteWithTokenLedger::try_from_slice(args)?;
+            (
+                ix.route_plan,
+                ix.slippage_bps,
+                ix.platform_fee_bps,
+                min_out(ix.quoted_out_amount, ix.slippage_bps),
+                2,
+                9,
+                6,
+                None,
+            )
+        } else if discriminator == SharedAccountsExactOutRoute::DISCRIMINATOR {
+            let ix = SharedAccountsExactOutRoute::try_from_slice(args)?;
+            (
+                ix.route_plan,
+                ix.slippage_bps,
+                ix.platform_fee_bps,
+                ix.out_amount,
+                2,
+                9,
+                6,
+                None,
+            )
+        } else {
+            return err!(CpiSwapError::UndecodedRoute);
+        };
+        let exact_out = discriminator == ExactOutRoute::DISCRIMINATOR
+            || discriminator == SharedAccountsExactOutRoute::DISCRIMINATOR;
+
+        Ok(Self {
+            route_plan,
+            slippage_bps,
+            platform_fee_bps,
+            min_amount_out,
+            user_transfer_authority_index,
+            platform_fee_account_index,
+            destination_account_index,
+            destination_override_index,
+            exact_out,
+        })
+    }
+
+    /// Length of the longest chain of steps from the input token to any
+    /// output, so split legs between the same pair of tokens count once.
+    pub fn hops(&self) -> u8 {
+        let mut depth = [0u8; 256];
+        let mut hops = 0;
+        for step in &self.route_plan {
+            let next = depth[step.input_index as usize].saturating_add(1);
+            let output = &mut depth[step.output_index as usize];
+            *output = (*output).max(next);
+            hops = hops.max(next);
+        }
+        hops
+    }
+
+    /// Borsh variant index of each step's venue in Jupiter's `Swap` enum.
+    pub fn venues(&self) -> Result<Vec<u8>> {
+        self.route_plan
+            .iter()
+            .map(|step| {
+                let encoded = step.swap.try_to_vec()?;
+                Ok(encoded[0])
+            })
+            .collect()
+    }
+}
+
+fn min_out(quoted_out_amount: u64, slippage_bps: u16) -> u64 {
+    let tolerance = 10_000u128.saturating_sub(slippage_bps as u128);
+    (quoted_out_amount as u128 * tolerance / 10_000) as u64
+}
+
+#[cfg(test)]
+impl DecodedRoute {
+    /// A decoded `route` instruction through `route_plan`, with no slippage,
+    /// platform fee or minimum output.
+    pub(crate) fn test_route(route_plan: Vec<RoutePlanStep>) -> Self {
+        Self {
+            route_plan,
+            slippage_bps: 0,
+            platform_fee_bps: 0,
+            min_amount_out: 0,
+            user_transfer_authority_index: 1,
+            platform_fee_account_index: 6,
+            destination_account_index: 3,
+            destination_override_index: Some(4),
+            exact_out: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::InstructionData;
+
+    use super::*;
+    use crate::jupiter_aggregator::types::Swap;
+
+    fn step(swap: Swap, percent: u8, input_index: u8, output_index: u8) -> RoutePlanStep {
+        RoutePlanStep {
+            swap,
+            percent,
+            input_index,
+            output_index,
+        }
+    }
+
+    #[test]
+    fn hops_count_split_legs_once() {
+        assert_eq!(DecodedRoute::test_route(vec![]).hops(), 0);
+        assert_eq!(
+            DecodedRoute::test_route(vec![step(Swap::Saber, 100, 0, 1)]).hops(),
+            1
+        );
+
+        // A -> B over two venues, then B -> C
+        let split = DecodedRoute::test_route(vec![
+            step(Swap::Saber, 50, 0, 1),
+            step(Swap::Raydium, 50, 0, 1),
+            step(Swap::Saber, 100, 1, 2),
+        ]);
+        assert_eq!(split.hops(), 2);
+    }
+
+    #[test]
+    fn hops_follow_the_longest_chain() {
+        // A -> C directly, and A -> B -> C
+        let route = DecodedRoute::test_route(vec![
+            step(Swap::Saber, 50, 0, 2),
+            step(Swap::Saber, 50, 0, 1),
+            step(Swap::Raydium, 100, 1, 2),
+        ]);
+        assert_eq!(route.hops(), 2);
+    }
+
+    #[test]
+    fn venues_are_swap_variant_indices() {
+        let route = DecodedRoute::test_route(vec![
+            step(Swap::Saber, 50, 0, 1),
+            step(Swap::Raydium, 50, 0, 1),
+        ]);
+        assert_eq!(route.venues().unwrap(), vec![0, 7]);
+    }
+
+    #[test]
+    fn min_out_takes_off_the_slippage() {
+        assert_eq!(min_out(1_000, 0), 1_000);
+        assert_eq!(min_out(1_000, 50), 995);
+        // Rounds down
+        assert_eq!(min_out(999, 50), 994);
+        assert_eq!(min_out(1_000, 10_000), 0);
+        assert_eq!(min_out(1_000, u16::MAX), 0);
+        assert_eq!(min_out(u64::MAX, 0), u64::MAX);
+    }
+
+    #[test]
+    fn decode_reads_route_arguments_and_accounts() {
+        let data = Route {
+            route_plan: vec![step(Swap::Saber, 100, 0, 1)],
+            in_amount: 1_000,
+            quoted_out_amount: 2_000,
+            slippage_bps: 100,
+            platform_fee_bps: 20,
+        }
+        .data();
+        let route = DecodedRoute::decode(&data).unwrap();
+
+        assert_eq!(route.hops(), 1);
+        assert_eq!(route.slippage_bps, 100);
+        assert_eq!(route.platform_fee_bps, 20);
+        assert_eq!(route.min_amount_out, 1_980);
+        assert_eq!(route.user_transfer_authority_index, 1);
+        assert_eq!(route.platform_fee_account_index, 6);
+        assert_eq!(route.destination_account_index, 3);
+        assert_eq!(route.destination_override_index, Some(4));
+        assert!(!route.exact_out);
+    }
+
+    #[test]
+    fn decode_reads_shared_exact_out_routes() {
+        let data = SharedAccountsExactOutRoute {
+            id: 0,
+            route_plan: vec![step(Swap::Saber, 100, 0, 1)],
+            out_amount: 2_000,
+            quoted_in_amount: 1_000,
+            slippage_bps: 100,
+            platform_fee_bps: 0,
+        }
+        .data();
+        let route = DecodedRoute::decode(&data).unwrap();
+
+        // Exact-out routes deliver exactly `out_amount`
+        assert_eq!(route.min_amount_out, 2_000);
+        assert_eq!(route.user_transfer_authority_index, 2);
+        assert_eq!(route.platform_fee_account_index, 9);
+        assert_eq!(route.destination_account_index, 6);
+        assert_eq!(route.destination_override_index, None);
+        assert!(route.exact_out);
+    }
+
+    #[test]
+    fn decode_rejects_unknown_and_short_data() {
+        assert!(DecodedRoute::decode(&[0; 4]).is_err());
+        assert!(DecodedRoute::decode(&[0; 64]).is_err());
+
+        let data = Route {
+            route_plan: vec![step(Swap::Saber, 100, 0, 1)],
+            in_amount: 1_000,
+            quoted_out_amount: 2_000,
+            slippage_bps: 100,
+            platform_fee_bps: 0,
+        }
+        .data();
+        assert!(DecodedRoute::decode(&data[..data.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{MAX_AGGREGATORS, MAX_AGGREGATOR_DISCRIMINATORS},
+    errors::CpiSwapError,
+};
+
+/// How the vault reads the route arguments of an aggregator's instructions.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum RouteDecoder {
+    /// Routes cannot be decoded and are only checked against the
+    /// discriminators.
+    None,
+    /// Jupiter v6 route instructions, at any program id.
+    JupiterV6,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct AggregatorEntry {
+    pub program_id: Pubkey,
+    pub decoder: RouteDecoder,
+    /// Instruction discriminators the vault may sign for on this program.
+    #[max_len(MAX_AGGREGATOR_DISCRIMINATORS)]
+    pub discriminators: Vec<[u8; 8]>,
+}
+
+/// Aggregator and router programs the vault is allowed to invoke.
+#[account]
+#[derive(InitSpace)]
+pub struct AggregatorRegistry {
+    #[max_len(MAX_AGGREGATORS)]
+    pub aggregators: Vec<AggregatorEntry>,
+    pub bump: u8,
+}
+
+impl AggregatorRegistry {
+    /// Returns the decoder registered for `program_id` if it may be invoked
+    /// with `data`.
+    pub fn check(&self, program_id: &Pubkey, data: &[u8]) -> Result<RouteDecoder> {
+        let entry = self
+            .aggregators
+            .iter()
+            .find(|entry| entry.program_id == *program_id)
+            .ok_or(CpiSwapError::AggregatorNotAllowed)?;
+
+        let discriminator = data.get(..8).ok_or(CpiSwapError::InstructionNotAllowed)?;
+        require!(
+            entry
+                .discriminators
+                .iter()
+                .any(|allowed| allowed.as_slice() == discriminator),
+            CpiSwapError::InstructionNotAllowed
+        );
+
+        Ok(entry.decoder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROUTE: [u8; 8] = [1; 8];
+
+    fn registry(program_id: Pubkey) -> AggregatorRegistry {
+        AggregatorRegistry {
+            aggregators: vec![AggregatorEntry {
+                program_id,
+                decoder: RouteDecoder::JupiterV6,
+                discriminators: vec![ROUTE],
+            }],
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn check_returns_the_entry_decoder() {
+        let program_id = Pubkey::new_unique();
+        let registry = registry(program_id);
+
+        let mut data = ROUTE.to_vec();
+        data.extend([0; 16]);
+        assert_eq!(
+            registry.check(&program_id, &data).unwrap(),
+            RouteDecoder::JupiterV6
+        );
+        assert_eq!(
+            registry.check(&program_id, &ROUTE).unwrap(),
+            RouteDecoder::JupiterV6
+        );
+    }
+
+    #[test]
+    fn check_rejects_unknown_programs_and_instructions() {
+        let program_id = Pubkey::new_unique();
+        let registry = registry(program_id);
+
+        assert_eq!(
+            registry.check(&Pubkey::new_unique(), &ROUTE).unwrap_err(),
+            CpiSwapError::AggregatorNotAllowed.into()
+        );
+        assert_eq!(
+            registry.check(&program_id, &[2; 8]).unwrap_err(),
+            CpiSwapError::InstructionNotAllowed.into()
+        );
+        assert_eq!(
+            registry.check(&program_id, &ROUTE[..7]).unwrap_err(),
+            CpiSwapError::InstructionNotAllowed.into()
+        );
+    }
+}
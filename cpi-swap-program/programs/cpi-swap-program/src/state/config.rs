@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct VaultConfig {
+    pub admin: Pubkey,
+    /// Owner that platform fee token accounts passed to `swap` must have.
+    pub referral_authority: Pubkey,
+    pub bump: u8,
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::PRICE_SCALE, errors::CpiSwapError};
+
+/// Terms a user sets, with their own signature, for delegated swaps of one
+/// mint pair out of their token account by one vault.
+#[account]
+#[derive(InitSpace)]
+pub struct DelegationTerms {
+    pub user: Pubkey,
+    pub vault: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    /// Least raw output per raw unit of input, scaled by `PRICE_SCALE`.
+    pub min_price: u64,
+    pub bump: u8,
+}
+
+impl DelegationTerms {
+    pub fn check_price(&self, amount_in: u64, amount_out: u64) -> Result<()> {
+        require!(
+            u128::from(amount_out) * PRICE_SCALE
+                >= u128::from(amount_in) * u128::from(self.min_price),
+            CpiSwapError::DelegatedPriceTooLow
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(min_price: u64) -> DelegationTerms {
+        DelegationTerms {
+            user: Pubkey::default(),
+            vault: Pubkey::default(),
+            input_mint: Pubkey::default(),
+            output_mint: Pubkey::default(),
+            min_price,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn check_price_accepts_exactly_the_floor() {
+        // 2.5 output per unit of input
+        let terms = terms(2_500_000_000);
+
+        terms.check_price(1_000, 2_500).unwrap();
+        assert_eq!(
+            terms.check_price(1_000, 2_499).unwrap_err(),
+            CpiSwapError::DelegatedPriceTooLow.into()
+        );
+        // A rounded-down floor still holds one raw unit below the exact price
+        terms.check_price(3, 8).unwrap();
+        assert!(terms.check_price(3, 7).is_err());
+    }
+
+    #[test]
+    fn check_price_does_not_overflow_at_the_extremes() {
+        let highest = terms(u64::MAX);
+        let scale = PRICE_SCALE as u64;
+
+        highest.check_price(scale, u64::MAX).unwrap();
+        assert!(highest.check_price(scale + 1, u64::MAX).is_err());
+        assert!(highest.check_price(u64::MAX, u64::MAX).is_err());
+
+        terms(scale).check_price(u64::MAX, u64::MAX).unwrap();
+        assert!(terms(scale).check_price(u64::MAX, u64::MAX - 1).is_err());
+        terms(0).check_price(u64::MAX, 0).unwrap();
+    }
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::REIMBURSEMENT_WINDOW_SECONDS;
+
+/// Lamports a vault pays back to the operator of each swap, capped per swap
+/// and per UTC day.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeReimbursement {
+    pub vault: Pubkey,
+    pub lamports_per_swap: u64,
+    pub max_lamports_per_day: u64,
+    pub day_start: i64,
+    pub day_lamports: u64,
+    pub bump: u8,
+}
+
+impl FeeReimbursement {
+    /// Records and returns what the next swap may be paid, given `spare`
+    /// lamports the vault can part with.
+    pub fn claim(&mut self, now: i64, spare: u64) -> u64 {
+        let day_start = now - now.rem_euclid(REIMBURSEMENT_WINDOW_SECONDS);
+        if self.day_start != day_start {
+            self.day_start = day_start;
+            self.day_lamports = 0;
+        }
+
+        let lamports = self
+            .lamports_per_swap
+            .min(self.max_lamports_per_day.saturating_sub(self.day_lamports))
+            .min(spare);
+        self.day_lamports += lamports;
+        lamports
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = REIMBURSEMENT_WINDOW_SECONDS;
+
+    fn reimbursement_of(lamports_per_swap: u64, max_lamports_per_day: u64) -> FeeReimbursement {
+        FeeReimbursement {
+            vault: Pubkey::default(),
+            lamports_per_swap,
+            max_lamports_per_day,
+            day_start: 0,
+            day_lamports: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn claim_is_capped_per_swap_per_day_and_by_spare_lamports() {
+        let mut reimbursement = reimbursement_of(5_000, 12_000);
+
+        assert_eq!(reimbursement.claim(DAY, u64::MAX), 5_000);
+        assert_eq!(reimbursement.claim(DAY + 1, u64::MAX), 5_000);
+        assert_eq!(reimbursement.claim(DAY + 2, u64::MAX), 2_000);
+        assert_eq!(reimbursement.claim(DAY + 3, u64::MAX), 0);
+        assert_eq!(reimbursement.day_lamports, 12_000);
+
+        let mut low_balance = reimbursement_of(5_000, 12_000);
+        assert_eq!(low_balance.claim(DAY, 1_000), 1_000);
+        assert_eq!(low_balance.day_lamports, 1_000);
+    }
+
+    #[test]
+    fn claim_resets_on_the_next_day() {
+        let mut reimbursement = reimbursement_of(5_000, 5_000);
+
+        assert_eq!(reimbursement.claim(DAY + 10, u64::MAX), 5_000);
+        assert_eq!(reimbursement.claim(2 * DAY - 1, u64::MAX), 0);
+        assert_eq!(reimbursement.claim(2 * DAY, u64::MAX), 5_000);
+        assert_eq!(reimbursement.day_start, 2 * DAY);
+        assert_eq!(reimbursement.day_lamports, 5_000);
+    }
+}
@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{MAX_HOOKS, MAX_HOOK_DISCRIMINATORS, MAX_HOOK_WRITABLE_ACCOUNTS},
+    errors::CpiSwapError,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub struct HookEntry {
+    pub program_id: Pubkey,
+    /// Instruction discriminators the vault may sign for after a swap.
+    #[max_len(MAX_HOOK_DISCRIMINATORS)]
+    pub discriminators: Vec<[u8; 8]>,
+    /// Accounts the hook may write to besides the vault and its output token
+    /// account, such as the market's reserve and the vault's receipt token
+    /// account. Pinning them keeps a manager from pointing the hook's
+    /// transfers at an account of their own.
+    #[max_len(MAX_HOOK_WRITABLE_ACCOUNTS)]
+    pub writable_accounts: Vec<Pubkey>,
+}
+
+impl HookEntry {
+    /// Checks that every writable account in `accounts` is one of `allowed`
+    /// or pinned for this hook.
+    pub fn check_writable_accounts(
+        &self,
+        accounts: &[AccountInfo],
+        allowed: &[Pubkey],
+    ) -> Result<()> {
+        for account in accounts.iter().filter(|account| account.is_writable) {
+            require!(
+                allowed.contains(account.key) || self.writable_accounts.contains(account.key),
+                CpiSwapError::HookAccountNotPinned
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Downstream programs the vault may invoke with a swap's output, such as a
+/// lending market deposit.
+#[account]
+#[derive(InitSpace)]
+pub struct HookRegistry {
+    #[max_len(MAX_HOOKS)]
+    pub hooks: Vec<HookEntry>,
+    pub bump: u8,
+}
+
+impl HookRegistry {
+    /// Returns the entry for `program_id` if it allows the instruction in
+    /// `data`.
+    pub fn check(&self, program_id: &Pubkey, data: &[u8]) -> Result<&HookEntry> {
+        let entry = self
+            .hooks
+            .iter()
+            .find(|entry| entry.program_id == *program_id)
+            .ok_or(CpiSwapError::HookNotAllowed)?;
+
+        let discriminator = data.get(..8).ok_or(CpiSwapError::InstructionNotAllowed)?;
+        require!(
+            entry
+                .discriminators
+                .iter()
+                .any(|allowed| allowed.as_slice() == discriminator),
+            CpiSwapError::InstructionNotAllowed
+        );
+
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEPOSIT: [u8; 8] = [3; 8];
+
+    #[test]
+    fn check_allows_only_listed_hook_instructions() {
+        let program_id = Pubkey::new_unique();
+        let registry = HookRegistry {
+            hooks: vec![HookEntry {
+                program_id,
+                discriminators: vec![DEPOSIT],
+                writable_accounts: vec![],
+            }],
+            bump: 0,
+        };
+
+        let mut data = DEPOSIT.to_vec();
+        data.extend(100u64.to_le_bytes());
+        registry.check(&program_id, &data).unwrap();
+
+        assert_eq!(
+            registry.check(&Pubkey::new_unique(), &data).unwrap_err(),
+            CpiSwapError::HookNotAllowed.into()
+        );
+        assert_eq!(
+            registry.check(&program_id, &[4; 8]).unwrap_err(),
+            CpiSwapError::InstructionNotAllowed.into()
+        );
+        assert_eq!(
+            registry.check(&program_id, &DEPOSIT[..7]).unwrap_err(),
+            CpiSwapError::InstructionNotAllowed.into()
+        );
+    }
+
+    #[test]
+    fn hook_writes_only_to_allowed_and_pinned_accounts() {
+        let (output, reserve, other) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let entry = HookEntry {
+            program_id: Pubkey::new_unique(),
+            discriminators: vec![DEPOSIT],
+            writable_accounts: vec![reserve],
+        };
+        let owner = Pubkey::new_unique();
+        let (mut lamports, mut data) = ([0u64; 4], [[0u8; 0]; 4]);
+        let mut lamports = lamports.iter_mut();
+        let mut data = data.iter_mut();
+        let mut info = |key, is_writable| {
+            AccountInfo::new(
+                key,
+                false,
+                is_writable,
+                lamports.next().unwrap(),
+                data.next().unwrap(),
+                &owner,
+                false,
+                0,
+            )
+        };
+        let accounts = [
+            info(&output, true),
+            info(&reserve, true),
+            info(&other, false),
+        ];
+
+        entry.check_writable_accounts(&accounts, &[output]).unwrap();
+        assert_eq!(
+            entry.check_writable_accounts(&accounts, &[]).unwrap_err(),
+            CpiSwapError::HookAccountNotPinned.into()
+        );
+
+        let accounts = [info(&other, true)];
+        assert_eq!(
+            entry
+                .check_writable_accounts(&accounts, &[output])
+                .unwrap_err(),
+            CpiSwapError::HookAccountNotPinned.into()
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+/// Off-chain order a user signs with their wallet key so that any relayer can
+/// submit it through `swap_intent`. Spends come from the user's token account
+/// on which the vault is delegate.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SwapIntent {
+    pub user: Pubkey,
+    /// Strategy whose vault the user approved as delegate.
+    pub strategy_id: u64,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    /// Most input the relayer's route may spend.
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    /// Unix timestamp after which the intent can no longer be executed.
+    pub expiry: i64,
+    /// Must equal the user's [`UserNonce`] at execution time.
+    pub nonce: u64,
+}
+
+impl SwapIntent {
+    /// Bytes the user signs: this program's id followed by the borsh encoded
+    /// intent, so a signature is never valid for another program.
+    pub fn message(&self) -> Result<Vec<u8>> {
+        let mut message = crate::ID.to_bytes().to_vec();
+        self.serialize(&mut message)?;
+        Ok(message)
+    }
+}
+
+/// Next intent nonce a user may sign. Bumped on every executed intent.
+#[account]
+#[derive(InitSpace)]
+pub struct UserNonce {
+    pub user: Pubkey,
+    pub nonce: u64,
+    pub bump: u8,
+}
@@ -0,0 +1,29 @@
+pub mod aggregator_registry;
+pub mod config;
+pub mod delegation_terms;
+pub mod fee_reimbursement;
+pub mod hook_registry;
+pub mod intent;
+pub mod order;
+pub mod route_policy;
+pub mod strategy;
+pub mod twap_order;
+pub mod vault_fees;
+pub mod vault_nav;
+pub mod vault_stats;
+pub mod volume;
+
+pub use aggregator_registry::*;
+pub use config::*;
+pub use delegation_terms::*;
+pub use fee_reimbursement::*;
+pub use hook_registry::*;
+pub use intent::*;
+pub use order::*;
+pub use route_policy::*;
+pub use strategy::*;
+pub use twap_order::*;
+pub use vault_fees::*;
+pub use vault_nav::*;
+pub use vault_stats::*;
+pub use volume::*;
@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FILL_DUST_BPS, PRICE_SCALE},
+    errors::CpiSwapError,
+    state::StrategyConfig,
+};
+
+/// An order account that keepers crank out of the vault. Every order lapses
+/// at `expires_at`. Managers may cancel an order at any time; once it is
+/// filled or has lapsed anyone may close it, so stale orders do not pile up.
+/// The rent always goes back to `creator`, who paid it.
+pub trait CrankableOrder {
+    fn creator(&self) -> Pubkey;
+
+    fn expires_at(&self) -> i64;
+
+    fn is_filled(&self) -> bool;
+
+    /// Whether no fill can happen anymore.
+    fn is_finished(&self, now: i64) -> bool {
+        self.is_filled() || now >= self.expires_at()
+    }
+
+    fn check_close(&self, strategy: &StrategyConfig, closer: &Pubkey, now: i64) -> Result<()> {
+        require!(
+            strategy.is_manager(closer) || self.is_finished(now),
+            CpiSwapError::OrderStillOpen
+        );
+        Ok(())
+    }
+}
+
+/// Fills of an order that keepers may fill in parts. The order is split into
+/// tranches, such as the slices of a TWAP order. A keeper fills any amount up
+/// to what is left of the open tranche, which stays open until no more than
+/// `FILL_DUST_BPS` of it is left. Routes quote no output for a few units of
+/// input, so a tranche waiting on its last units could never close.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct OrderFills {
+    pub filled_in: u64,
+    pub filled_out: u64,
+    /// Input spent so far on the open tranche.
+    pub tranche_filled_in: u64,
+    pub last_fill_time: i64,
+}
+
+impl OrderFills {
+    /// Returns the input left in the open tranche of `tranche_in`.
+    pub fn tranche_left(&self, tranche_in: u64) -> u64 {
+        tranche_in.saturating_sub(self.tranche_filled_in)
+    }
+
+    /// Average raw output per raw unit of input over all fills, scaled by
+    /// `PRICE_SCALE`, or zero before the first fill.
+    pub fn average_price(&self) -> u64 {
+        match self.filled_in {
+            0 => 0,
+            filled_in => (u128::from(self.filled_out) * PRICE_SCALE / u128::from(filled_in))
+                .try_into()
+                .unwrap_or(u64::MAX),
+        }
+    }
+
+    /// Adds a fill to the open tranche of `tranche_in` and returns whether
+    /// the fill used the tranche up, leaving at most dust.
+    pub fn record(
+        &mut self,
+        amount_in: u64,
+        amount_out: u64,
+        tranche_in: u64,
+        now: i64,
+    ) -> Result<bool> {
+        require!(amount_in > 0 && amount_out > 0, CpiSwapError::EmptyFill);
+        require_gte!(
+            self.tranche_left(tranche_in),
+            amount_in,
+            CpiSwapError::FillExceedsTranche
+        );
+
+        self.filled_in = self
+            .filled_in
+            .checked_add(amount_in)
+            .ok_or(CpiSwapError::MathOverflow)?;
+        self.filled_out = self
+            .filled_out
+            .checked_add(amount_out)
+            .ok_or(CpiSwapError::MathOverflow)?;
+        self.tranche_filled_in += amount_in;
+        self.last_fill_time = now;
+
+        let left = u128::from(self.tranche_left(tranche_in));
+        if left * 10_000 > u128::from(tranche_in) * u128::from(FILL_DUST_BPS) {
+            return Ok(false);
+        }
+        self.tranche_filled_in = 0;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestOrder {
+        filled: bool,
+    }
+
+    impl CrankableOrder for TestOrder {
+        fn creator(&self) -> Pubkey {
+            Pubkey::default()
+        }
+
+        fn expires_at(&self) -> i64 {
+            100
+        }
+
+        fn is_filled(&self) -> bool {
+            self.filled
+        }
+    }
+
+    #[test]
+    fn only_managers_close_open_orders() {
+        let manager = Pubkey::new_unique();
+        let strategy = StrategyConfig {
+            strategy_id: 0,
+            managers: vec![manager],
+            keepers: vec![],
+            allowed_mints: vec![],
+            recipients: vec![],
+            max_slippage_bps: 0,
+            platform_fee_bps: 0,
+            max_keeper_reward: 0,
+            max_order_keeper_rewards: 0,
+            vault_bump: 0,
+            bump: 0,
+        };
+        let anyone = Pubkey::new_unique();
+        let open = TestOrder { filled: false };
+
+        open.check_close(&strategy, &manager, 99).unwrap();
+        assert_eq!(
+            open.check_close(&strategy, &anyone, 99).unwrap_err(),
+            CpiSwapError::OrderStillOpen.into()
+        );
+        open.check_close(&strategy, &anyone, 100).unwrap();
+        TestOrder { filled: true }
+            .check_close(&strategy, &anyone, 0)
+            .unwrap();
+    }
+
+    #[test]
+    fn partial_fills_keep_the_tranche_open() {
+        let mut fills = OrderFills::default();
+
+        assert!(!fills.record(100, 200, 250, 1).unwrap());
+        assert_eq!(fills.tranche_left(250), 150);
+        assert_eq!(
+            fills.clone().record(151, 302, 250, 2).unwrap_err(),
+            CpiSwapError::FillExceedsTranche.into()
+        );
+        assert!(fills.record(150, 300, 250, 2).unwrap());
+
+        assert_eq!(fills.tranche_left(250), 250);
+        assert_eq!(fills.filled_in, 250);
+        assert_eq!(fills.filled_out, 500);
+        assert_eq!(fills.last_fill_time, 2);
+    }
+
+    #[test]
+    fn tranches_close_with_dust_left() {
+        let mut fills = OrderFills::default();
+
+        // 10 of a 10_000 tranche is dust, 11 is not
+        assert!(!fills.record(9_989, 9_989, 10_000, 1).unwrap());
+        assert_eq!(fills.tranche_left(10_000), 11);
+        assert!(fills.record(1, 1, 10_000, 2).unwrap());
+        assert_eq!(fills.tranche_left(10_000), 10_000);
+        assert_eq!(fills.filled_in, 9_990);
+    }
+
+    #[test]
+    fn fills_must_swap_something() {
+        let mut fills = OrderFills::default();
+
+        assert_eq!(
+            fills.record(0, 1, 250, 1).unwrap_err(),
+            CpiSwapError::EmptyFill.into()
+        );
+        assert_eq!(
+            fills.record(1, 0, 250, 1).unwrap_err(),
+            CpiSwapError::EmptyFill.into()
+        );
+    }
+
+    #[test]
+    fn average_price_covers_all_fills() {
+        let mut fills = OrderFills::default();
+        assert_eq!(fills.average_price(), 0);
+
+        fills.record(100, 200, 1_000, 1).unwrap();
+        fills.record(100, 202, 1_000, 2).unwrap();
+        assert_eq!(fills.average_price(), 201 * PRICE_SCALE as u64 / 100);
+    }
+}
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::MAX_POLICY_VENUES, errors::CpiSwapError, route_plan::DecodedRoute};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum VenueFilter {
+    /// Every venue except those listed is allowed.
+    Deny,
+    /// Only the listed venues are allowed.
+    Allow,
+}
+
+/// Limits on the Jupiter route plans a vault may execute.
+#[account]
+#[derive(InitSpace)]
+pub struct RoutePolicy {
+    pub vault: Pubkey,
+    pub max_hops: u8,
+    pub max_slippage_bps: u16,
+    pub venue_filter: VenueFilter,
+    /// Variant indices of Jupiter's `Swap` enum.
+    #[max_len(MAX_POLICY_VENUES)]
+    pub venues: Vec<u8>,
+    /// Whether registered aggregators without a route decoder may be invoked
+    /// by `swap_from_user`. Routes the vault signs for must always decode.
+    pub allow_undecoded_routes: bool,
+    pub bump: u8,
+}
+
+impl RoutePolicy {
+    /// `route` is `None` when the aggregator has no route decoder.
+    pub fn check(&self, route: Option<&DecodedRoute>) -> Result<()> {
+        let Some(route) = route else {
+            require!(self.allow_undecoded_routes, CpiSwapError::UndecodedRoute);
+            return Ok(());
+        };
+
+        require_gte!(self.max_hops, route.hops(), CpiSwapError::TooManyHops);
+        require_gte!(
+            self.max_slippage_bps,
+            route.slippage_bps,
+            CpiSwapError::SlippageTooHigh
+        );
+        for venue in route.venues()? {
+            let listed = self.venues.contains(&venue);
+            let allowed = match self.venue_filter {
+                VenueFilter::Deny => !listed,
+                VenueFilter::Allow => listed,
+            };
+            require!(allowed, CpiSwapError::VenueNotAllowed);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jupiter_aggregator::types::{RoutePlanStep, Swap};
+
+    fn policy(venue_filter: VenueFilter, venues: Vec<u8>) -> RoutePolicy {
+        RoutePolicy {
+            vault: Pubkey::default(),
+            max_hops: 2,
+            max_slippage_bps: 100,
+            venue_filter,
+            venues,
+            allow_undecoded_routes: false,
+            bump: 0,
+        }
+    }
+
+    /// A route through `swaps` in sequence.
+    fn route(swaps: Vec<Swap>, slippage_bps: u16) -> DecodedRoute {
+        let mut route = DecodedRoute::test_route(
+            swaps
+                .into_iter()
+                .enumerate()
+                .map(|(i, swap)| RoutePlanStep {
+                    swap,
+                    percent: 100,
+                    input_index: i as u8,
+                    output_index: i as u8 + 1,
+                })
+                .collect(),
+        );
+        route.slippage_bps = slippage_bps;
+        route
+    }
+
+    #[test]
+    fn check_limits_hops_and_slippage() {
+        let policy = policy(VenueFilter::Deny, vec![]);
+
+        policy
+            .check(Some(&route(vec![Swap::Saber, Swap::Saber], 100)))
+            .unwrap();
+        assert!(policy
+            .check(Some(&route(
+                vec![Swap::Saber, Swap::Saber, Swap::Saber],
+                100
+            )))
+            .is_err());
+        assert!(policy.check(Some(&route(vec![Swap::Saber], 101))).is_err());
+    }
+
+    #[test]
+    fn check_applies_the_venue_filter() {
+        // Saber is variant 0 and Raydium variant 7
+        let deny = policy(VenueFilter::Deny, vec![7]);
+        deny.check(Some(&route(vec![Swap::Saber], 0))).unwrap();
+        assert!(deny
+            .check(Some(&route(vec![Swap::Saber, Swap::Raydium], 0)))
+            .is_err());
+
+        let allow = policy(VenueFilter::Allow, vec![7]);
+        allow.check(Some(&route(vec![Swap::Raydium], 0))).unwrap();
+        assert!(allow
+            .check(Some(&route(vec![Swap::Raydium, Swap::Saber], 0)))
+            .is_err());
+    }
+
+    #[test]
+    fn check_gates_undecoded_routes() {
+        let mut policy = policy(VenueFilter::Deny, vec![]);
+        assert!(policy.check(None).is_err());
+
+        policy.allow_undecoded_routes = true;
+        policy.check(None).unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{MAX_KEEPERS, MAX_MANAGERS, MAX_RECIPIENTS, MAX_STRATEGY_MINTS},
+    errors::CpiSwapError,
+    route_plan::DecodedRoute,
+};
+
+/// Risk limits and roles for one strategy and its vault, derived from
+/// `[VAULT_SEED, strategy_id]`. Everything keyed by the vault address, such
+/// as route policies, volume limits and stats, is scoped to the strategy.
+///
+/// The config admin is the only role that can move funds out of the vault.
+/// Managers trade and manage orders, keepers can only crank fills of orders
+/// that already exist.
+#[account]
+#[derive(InitSpace)]
+pub struct StrategyConfig {
+    pub strategy_id: u64,
+    #[max_len(MAX_MANAGERS)]
+    pub managers: Vec<Pubkey>,
+    #[max_len(MAX_KEEPERS)]
+    pub keepers: Vec<Pubkey>,
+    /// Mints the vault may swap from or into. Empty allows every mint.
+    #[max_len(MAX_STRATEGY_MINTS)]
+    pub allowed_mints: Vec<Pubkey>,
+    /// Owners of the token accounts swap output may be forwarded to.
+    #[max_len(MAX_RECIPIENTS)]
+    pub recipients: Vec<Pubkey>,
+    pub max_slippage_bps: u16,
+    /// Platform fee routes must charge into the config's referral accounts.
+    pub platform_fee_bps: u8,
+    /// Most lamports an order may pay its keeper for one fill.
+    pub max_keeper_reward: u64,
+    /// Most lamports an order may pay its keepers over all its fills.
+    pub max_order_keeper_rewards: u64,
+    pub vault_bump: u8,
+    pub bump: u8,
+}
+
+impl StrategyConfig {
+    pub fn is_manager(&self, key: &Pubkey) -> bool {
+        self.managers.contains(key)
+    }
+
+    pub fn is_keeper(&self, key: &Pubkey) -> bool {
+        self.keepers.contains(key)
+    }
+
+    pub fn allows_mint(&self, mint: &Pubkey) -> bool {
+        self.allowed_mints.is_empty() || self.allowed_mints.contains(mint)
+    }
+
+    pub fn allows_recipient(&self, owner: &Pubkey) -> bool {
+        self.recipients.contains(owner)
+    }
+
+    /// Checks an order's `keeper_reward` per fill over `fills` fills against
+    /// the caps.
+    pub fn check_keeper_reward(&self, keeper_reward: u64, fills: u16) -> Result<()> {
+        require_gte!(
+            self.max_keeper_reward,
+            keeper_reward,
+            CpiSwapError::KeeperRewardTooHigh
+        );
+        let order_rewards = keeper_reward
+            .checked_mul(u64::from(fills))
+            .ok_or(CpiSwapError::KeeperRewardTooHigh)?;
+        require_gte!(
+            self.max_order_keeper_rewards,
+            order_rewards,
+            CpiSwapError::KeeperRewardTooHigh
+        );
+        Ok(())
+    }
+
+    /// Returns what a keeper may be paid for a fill that earns `keeper_reward`
+    /// on an order that already paid `rewards_paid`, under the current caps.
+    /// The admin may have lowered them since the order was created.
+    pub fn capped_keeper_reward(&self, keeper_reward: u64, rewards_paid: u64) -> u64 {
+        keeper_reward
+            .min(self.max_keeper_reward)
+            .min(self.max_order_keeper_rewards.saturating_sub(rewards_paid))
+    }
+
+    /// `route` is `None` when the aggregator has no route decoder.
+    pub fn check_route(&self, route: Option<&DecodedRoute>) -> Result<()> {
+        if let Some(route) = route {
+            require_gte!(
+                self.max_slippage_bps,
+                route.slippage_bps,
+                CpiSwapError::SlippageTooHigh
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strategy_with_keeper_caps(
+        max_keeper_reward: u64,
+        max_order_keeper_rewards: u64,
+    ) -> StrategyConfig {
+        StrategyConfig {
+            strategy_id: 0,
+            managers: vec![],
+            keepers: vec![],
+            allowed_mints: vec![],
+            recipients: vec![],
+            max_slippage_bps: 0,
+            platform_fee_bps: 0,
+            max_keeper_reward,
+            max_order_keeper_rewards,
+            vault_bump: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn keeper_rewards_are_capped_per_fill_and_per_order() {
+        let strategy = strategy_with_keeper_caps(1_000, 3_000);
+
+        strategy.check_keeper_reward(750, 4).unwrap();
+        assert!(strategy.check_keeper_reward(1_001, 1).is_err());
+        assert!(strategy.check_keeper_reward(751, 4).is_err());
+        assert!(strategy.check_keeper_reward(u64::MAX, u16::MAX).is_err());
+    }
+
+    #[test]
+    fn capped_keeper_reward_follows_lowered_caps() {
+        assert_eq!(
+            strategy_with_keeper_caps(1_000, 4_000).capped_keeper_reward(1_000, 0),
+            1_000
+        );
+        assert_eq!(
+            strategy_with_keeper_caps(400, 4_000).capped_keeper_reward(1_000, 0),
+            400
+        );
+        assert_eq!(
+            strategy_with_keeper_caps(1_000, 4_000).capped_keeper_reward(1_000, 3_500),
+            500
+        );
+        assert_eq!(
+            strategy_with_keeper_caps(1_000, 3_000).capped_keeper_reward(1_000, 3_500),
+            0
+        );
+    }
+}
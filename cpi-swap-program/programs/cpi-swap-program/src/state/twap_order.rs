@@ -0,0 +1,325 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::PRICE_SCALE,
+    errors::CpiSwapError,
+    state::{CrankableOrder, OrderFills, StrategyConfig},
+};
+
+/// Sells `total_in` of the vault's input mint in `slices` parts spread over
+/// `[start_time, end_time)`. Slice `i` opens at the `i`-th interval of the
+/// window and no sooner than one interval after the previous slice was
+/// completed, so missed slices are not caught up on all at once. A slice may
+/// be filled in several parts and stays open until its input is spent, but
+/// for dust that is spread over the slices after it. Unfilled slices lapse at
+/// `end_time`.
+#[account]
+#[derive(InitSpace)]
+pub struct TwapOrder {
+    pub vault: Pubkey,
+    pub order_id: u64,
+    /// Manager that paid for the account and gets the rent back on close.
+    pub creator: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub total_in: u64,
+    pub slices: u16,
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Least raw output per raw unit of input any slice may fill at, scaled
+    /// by `PRICE_SCALE`.
+    pub min_price: u64,
+    /// Largest deviation of a slice's price from the running average fill
+    /// price, in basis points of the average.
+    pub max_deviation_bps: u16,
+    /// Lamports the vault pays the keepers for each slice, out of its SOL
+    /// above rent. Partial fills are paid in proportion to the input they
+    /// spend.
+    pub keeper_reward: u64,
+    /// Slices whose input has been spent in full.
+    pub filled_slices: u16,
+    /// Fills so far, with the open slice as the tranche.
+    pub fills: OrderFills,
+    pub rewards_paid: u64,
+    pub bump: u8,
+}
+
+impl CrankableOrder for TwapOrder {
+    fn creator(&self) -> Pubkey {
+        self.creator
+    }
+
+    fn expires_at(&self) -> i64 {
+        self.end_time
+    }
+
+    fn is_filled(&self) -> bool {
+        self.filled_slices >= self.slices
+    }
+}
+
+impl TwapOrder {
+    fn interval(&self) -> i64 {
+        (self.end_time - self.start_time) / i64::from(self.slices)
+    }
+
+    /// Returns the most input the next slice may spend at `now`.
+    pub fn next_slice(&self, now: i64) -> Result<u64> {
+        require_gt!(
+            self.slices,
+            self.filled_slices,
+            CpiSwapError::TwapOrderFilled
+        );
+        require_gt!(self.end_time, now, CpiSwapError::TwapOrderExpired);
+
+        // A partly filled slice is already open
+        if self.fills.tranche_filled_in == 0 {
+            let interval = self.interval();
+            let scheduled = self.start_time + interval * i64::from(self.filled_slices);
+            let opens_at = match self.filled_slices {
+                0 => scheduled,
+                _ => scheduled.max(self.fills.last_fill_time + interval),
+            };
+            require_gte!(now, opens_at, CpiSwapError::TwapSliceNotReady);
+        }
+
+        Ok(self.fills.tranche_left(self.slice_in()))
+    }
+
+    /// Returns the open slice's share of the input left when it opened.
+    pub fn slice_in(&self) -> u64 {
+        let remaining_in = self.total_in - self.fills.filled_in + self.fills.tranche_filled_in;
+        let remaining_slices = u64::from(self.slices - self.filled_slices);
+        remaining_in.div_ceil(remaining_slices)
+    }
+
+    /// Returns the keeper's reward for a fill of `amount_in` out of a slice
+    /// of `slice_in`, under the strategy's current caps, which the admin may
+    /// have lowered since the order was created. Partial fills earn their
+    /// share of the slice's reward, so splitting a slice earns nothing extra.
+    pub fn keeper_reward_due(
+        &self,
+        strategy: &StrategyConfig,
+        amount_in: u64,
+        slice_in: u64,
+    ) -> u64 {
+        let slice_reward = self.keeper_reward.min(strategy.max_keeper_reward);
+        let reward = u128::from(slice_reward) * u128::from(amount_in) / u128::from(slice_in.max(1));
+        strategy.capped_keeper_reward(reward as u64, self.rewards_paid)
+    }
+
+    /// Adds a fill of up to the open slice's remaining input after checking
+    /// its price against the order's minimum and the average of earlier
+    /// fills. Returns whether the fill completed the slice.
+    pub fn record_fill(&mut self, amount_in: u64, amount_out: u64, now: i64) -> Result<bool> {
+        let earlier = self.fills;
+        let slice_filled = self
+            .fills
+            .record(amount_in, amount_out, self.slice_in(), now)?;
+
+        let price = u128::from(amount_out) * PRICE_SCALE / u128::from(amount_in);
+        require_gte!(
+            price,
+            u128::from(self.min_price),
+            CpiSwapError::TwapPriceDeviation
+        );
+        if earlier.filled_in > 0 {
+            let average =
+                u128::from(earlier.filled_out) * PRICE_SCALE / u128::from(earlier.filled_in);
+            let deviation = average.abs_diff(price) * 10_000;
+            require_gte!(
+                average * u128::from(self.max_deviation_bps),
+                deviation,
+                CpiSwapError::TwapPriceDeviation
+            );
+        }
+
+        if slice_filled {
+            self.filled_slices += 1;
+        }
+        Ok(slice_filled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: i64 = 1_000;
+
+    /// 1_000 input in 4 slices over 400 seconds, with one slice every 100.
+    fn order() -> TwapOrder {
+        TwapOrder {
+            vault: Pubkey::default(),
+            order_id: 0,
+            creator: Pubkey::default(),
+            input_mint: Pubkey::default(),
+            output_mint: Pubkey::default(),
+            total_in: 1_000,
+            slices: 4,
+            start_time: START,
+            end_time: START + 400,
+            min_price: PRICE_SCALE as u64,
+            max_deviation_bps: 100,
+            keeper_reward: 0,
+            filled_slices: 0,
+            fills: OrderFills::default(),
+            rewards_paid: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn next_slice_follows_the_schedule() {
+        let mut order = order();
+
+        assert!(order.next_slice(START - 1).is_err());
+        assert_eq!(order.next_slice(START).unwrap(), 250);
+        order.record_fill(250, 250, START).unwrap();
+
+        assert!(order.next_slice(START + 99).is_err());
+        assert_eq!(order.next_slice(START + 100).unwrap(), 250);
+    }
+
+    #[test]
+    fn missed_slices_are_not_caught_up_at_once() {
+        let mut order = order();
+
+        order.record_fill(250, 250, START + 250).unwrap();
+        // Slices 1 and 2 are both scheduled, but one interval must pass first
+        assert!(order.next_slice(START + 300).is_err());
+        assert_eq!(order.next_slice(START + 350).unwrap(), 250);
+    }
+
+    #[test]
+    fn partial_fills_keep_the_slice_open() {
+        let mut order = order();
+
+        assert!(!order.record_fill(100, 100, START).unwrap());
+        assert_eq!(order.filled_slices, 0);
+        // The rest of the slice can be filled right away
+        assert_eq!(order.next_slice(START + 10).unwrap(), 150);
+        assert_eq!(
+            order.clone().record_fill(151, 151, START + 10).unwrap_err(),
+            CpiSwapError::FillExceedsTranche.into()
+        );
+        assert!(order.record_fill(150, 150, START + 10).unwrap());
+        assert_eq!(order.filled_slices, 1);
+
+        // The next slice waits an interval after the one before completed
+        assert!(order.next_slice(START + 109).is_err());
+        assert_eq!(order.next_slice(START + 110).unwrap(), 250);
+    }
+
+    #[test]
+    fn next_slice_spreads_the_remainder_and_stops() {
+        let mut order = order();
+        order.total_in = 1_001;
+
+        assert_eq!(order.next_slice(START).unwrap(), 251);
+        order.record_fill(251, 251, START).unwrap();
+        assert_eq!(order.next_slice(START + 100).unwrap(), 250);
+        order.record_fill(200, 200, START + 100).unwrap();
+        // A partly filled slice keeps the size it opened with
+        assert_eq!(order.slice_in(), 250);
+        order.record_fill(50, 50, START + 100).unwrap();
+        order.record_fill(250, 250, START + 200).unwrap();
+        order.record_fill(250, 250, START + 300).unwrap();
+
+        assert_eq!(order.fills.filled_in, order.total_in);
+        assert!(order.is_finished(START + 300));
+        assert!(order.next_slice(START + 399).is_err());
+    }
+
+    #[test]
+    fn dust_left_in_a_slice_moves_to_the_next() {
+        let mut order = order();
+        order.total_in = 100_000;
+
+        // 20 of a 25_000 slice is dust
+        assert!(order.record_fill(24_980, 24_980, START).unwrap());
+        assert_eq!(order.filled_slices, 1);
+        assert_eq!(order.next_slice(START + 100).unwrap(), 25_007);
+    }
+
+    #[test]
+    fn next_slice_fails_after_the_window() {
+        let order = order();
+        assert!(!order.is_finished(START + 399));
+        assert!(order.is_finished(START + 400));
+        assert!(order.next_slice(START + 400).is_err());
+    }
+
+    #[test]
+    fn record_fill_checks_the_minimum_price() {
+        let mut order = order();
+
+        assert!(order.clone().record_fill(250, 0, START).is_err());
+        assert!(order.clone().record_fill(0, 250, START).is_err());
+        // The first slice is bounded by the minimum price too
+        assert_eq!(
+            order.clone().record_fill(250, 249, START).unwrap_err(),
+            CpiSwapError::TwapPriceDeviation.into()
+        );
+        order.record_fill(250, 250, START).unwrap();
+    }
+
+    #[test]
+    fn record_fill_checks_deviation_from_the_average() {
+        let mut order = order();
+
+        order.record_fill(100, 200, START).unwrap();
+        assert_eq!(
+            order
+                .clone()
+                .record_fill(100, 203, START + 100)
+                .unwrap_err(),
+            CpiSwapError::TwapPriceDeviation.into()
+        );
+        assert!(order.clone().record_fill(100, 197, START + 100).is_err());
+        order.record_fill(100, 202, START + 100).unwrap();
+
+        assert_eq!(order.filled_slices, 0);
+        assert_eq!(order.fills.filled_in, 200);
+        assert_eq!(order.fills.filled_out, 402);
+        assert_eq!(order.fills.last_fill_time, START + 100);
+    }
+
+    fn strategy_with_keeper_caps(
+        max_keeper_reward: u64,
+        max_order_keeper_rewards: u64,
+    ) -> StrategyConfig {
+        StrategyConfig {
+            strategy_id: 0,
+            managers: vec![],
+            keepers: vec![],
+            allowed_mints: vec![],
+            recipients: vec![],
+            max_slippage_bps: 0,
+            platform_fee_bps: 0,
+            max_keeper_reward,
+            max_order_keeper_rewards,
+            vault_bump: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn partial_fills_earn_their_share_of_the_reward() {
+        let mut order = order();
+        order.keeper_reward = 1_000;
+        let strategy = strategy_with_keeper_caps(1_000, 4_000);
+
+        assert_eq!(order.keeper_reward_due(&strategy, 250, 250), 1_000);
+        assert_eq!(order.keeper_reward_due(&strategy, 100, 250), 400);
+        assert_eq!(order.keeper_reward_due(&strategy, 1, 250), 4);
+        // Rounding down keeps many tiny fills from earning more than one
+        assert_eq!(order.keeper_reward_due(&strategy, 1, 2_000), 0);
+        // The per-fill cap applies to the whole slice before it is split
+        let strategy = strategy_with_keeper_caps(400, 4_000);
+        assert_eq!(order.keeper_reward_due(&strategy, 125, 250), 200);
+
+        order.rewards_paid = 3_900;
+        assert_eq!(order.keeper_reward_due(&strategy, 250, 250), 100);
+    }
+}
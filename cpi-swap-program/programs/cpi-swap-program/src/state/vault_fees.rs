@@ -0,0 +1,202 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{PRICE_SCALE, SECONDS_PER_YEAR},
+    errors::CpiSwapError,
+};
+
+/// Management and performance fees of a share-based vault. Fees are paid by
+/// minting new shares of `share_mint` to `fee_recipient`, so holders pay them
+/// by dilution and the vault's tokens stay put.
+#[account]
+#[derive(InitSpace)]
+pub struct VaultFees {
+    pub vault: Pubkey,
+    pub share_mint: Pubkey,
+    pub fee_recipient: Pubkey,
+    /// Yearly fee on the NAV, in basis points, charged pro rata per second.
+    pub management_fee_bps: u16,
+    /// Fee on gains in NAV per share above `high_water_mark`, in basis
+    /// points.
+    pub performance_fee_bps: u16,
+    /// Highest NAV per share, after fees, that fees were charged up to,
+    /// scaled by `PRICE_SCALE`. Zero until the first accrual with shares out.
+    pub high_water_mark: u128,
+    /// Unix time of the last accrual.
+    pub accrued_at: i64,
+    pub bump: u8,
+}
+
+/// Fees charged by one accrual, in the NAV's units, and the shares minted for
+/// them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeAccrual {
+    pub management_fee: u64,
+    pub performance_fee: u64,
+    pub shares: u64,
+}
+
+impl VaultFees {
+    /// Makes the next accrual restart the high-water mark without charging
+    /// fees, for when the NAV is no longer comparable to the last one.
+    pub fn restart(&mut self) {
+        self.high_water_mark = 0;
+    }
+
+    /// Charges the fees due since the last accrual on a vault worth `nav`
+    /// with `supply` shares out, moves the high-water mark and returns the
+    /// shares to mint. The first accrual with shares out only starts the
+    /// clock and sets the mark. Since the mark is per share, shares issued or
+    /// redeemed at NAV per share are not charged as performance.
+    pub fn accrue(&mut self, nav: u64, supply: u64, now: i64) -> Result<FeeAccrual> {
+        let elapsed = now
+            .saturating_sub(self.accrued_at)
+            .clamp(0, i64::from(u32::MAX));
+        let started = self.accrued_at != 0 && self.high_water_mark != 0;
+        self.accrued_at = now;
+        if supply == 0 {
+            // The mark restarts with the next shares issued
+            self.high_water_mark = 0;
+            return Ok(FeeAccrual::default());
+        }
+
+        let nav = u128::from(nav);
+        let supply = u128::from(supply);
+        if !started {
+            self.high_water_mark = nav * PRICE_SCALE / supply;
+            return Ok(FeeAccrual::default());
+        }
+
+        let management_fee = (nav * u128::from(self.management_fee_bps) * elapsed as u128
+            / (10_000 * SECONDS_PER_YEAR as u128))
+            .min(nav);
+        let nav_per_share = (nav - management_fee) * PRICE_SCALE / supply;
+        let gain = nav_per_share.saturating_sub(self.high_water_mark);
+        let performance_fee =
+            gain * supply * u128::from(self.performance_fee_bps) / (PRICE_SCALE * 10_000);
+
+        let fee = management_fee + performance_fee;
+        let remaining = nav - fee;
+        // Shares worth `fee` once they are minted
+        let shares = match fee {
+            0 => 0,
+            _ => supply * fee / remaining.max(1),
+        };
+        self.high_water_mark = self.high_water_mark.max(remaining * PRICE_SCALE / supply);
+
+        Ok(FeeAccrual {
+            management_fee: management_fee as u64,
+            performance_fee: performance_fee as u64,
+            shares: shares.try_into().map_err(|_| CpiSwapError::MathOverflow)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: i64 = 1_000;
+
+    fn fees(management_fee_bps: u16, performance_fee_bps: u16) -> VaultFees {
+        VaultFees {
+            vault: Pubkey::default(),
+            share_mint: Pubkey::default(),
+            fee_recipient: Pubkey::default(),
+            management_fee_bps,
+            performance_fee_bps,
+            high_water_mark: 0,
+            accrued_at: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn first_accrual_only_sets_the_mark() {
+        let mut fees = fees(200, 2_000);
+
+        assert_eq!(
+            fees.accrue(1_000_000, 0, START).unwrap(),
+            FeeAccrual::default()
+        );
+        assert_eq!(fees.high_water_mark, 0);
+        assert_eq!(
+            fees.accrue(1_000_000, 500_000, START + 10).unwrap(),
+            FeeAccrual::default()
+        );
+        assert_eq!(fees.high_water_mark, 2 * PRICE_SCALE);
+        assert_eq!(fees.accrued_at, START + 10);
+    }
+
+    #[test]
+    fn management_fee_accrues_pro_rata() {
+        let mut fees = fees(200, 0);
+        fees.accrue(1_000_000, 1_000_000, START).unwrap();
+
+        let accrual = fees
+            .accrue(1_000_000, 1_000_000, START + SECONDS_PER_YEAR / 2)
+            .unwrap();
+        assert_eq!(accrual.management_fee, 10_000);
+        assert_eq!(accrual.performance_fee, 0);
+        // 10_101 new shares hold 10_000 of the 1_000_000 NAV
+        assert_eq!(accrual.shares, 10_101);
+        // A falling NAV per share leaves the mark where it was
+        assert_eq!(fees.high_water_mark, PRICE_SCALE);
+    }
+
+    #[test]
+    fn performance_fee_is_charged_above_the_mark_only() {
+        let mut fees = fees(0, 2_000);
+        fees.accrue(1_000_000, 1_000_000, START).unwrap();
+
+        let accrual = fees.accrue(1_100_000, 1_000_000, START + 1).unwrap();
+        assert_eq!(accrual.performance_fee, 20_000);
+        assert_eq!(accrual.shares, 18_518);
+        assert_eq!(fees.high_water_mark, 1_080_000_000);
+
+        // Losing and regaining the gain charges nothing
+        fees.accrue(1_000_000, 1_018_518, START + 2).unwrap();
+        let accrual = fees.accrue(1_100_000, 1_018_518, START + 3).unwrap();
+        assert_eq!(accrual, FeeAccrual::default());
+    }
+
+    #[test]
+    fn issuing_shares_at_nav_is_not_performance() {
+        let mut fees = fees(0, 2_000);
+        fees.accrue(1_000_000, 1_000_000, START).unwrap();
+
+        // A deposit doubles the NAV along with the shares
+        let accrual = fees.accrue(2_000_000, 2_000_000, START + 1).unwrap();
+        assert_eq!(accrual, FeeAccrual::default());
+        assert_eq!(fees.high_water_mark, PRICE_SCALE);
+    }
+
+    #[test]
+    fn restart_resets_the_mark_without_charging() {
+        let mut fees = fees(200, 2_000);
+        fees.accrue(1_000_000, 1_000_000, START).unwrap();
+
+        // Registering another asset raised the NAV, which is not performance
+        fees.restart();
+        let accrual = fees.accrue(1_500_000, 1_000_000, START + 100).unwrap();
+        assert_eq!(accrual, FeeAccrual::default());
+        assert_eq!(fees.high_water_mark, 1_500_000_000);
+        assert_eq!(fees.accrued_at, START + 100);
+    }
+
+    #[test]
+    fn elapsed_time_is_clamped() {
+        let mut fees = fees(10_000, 0);
+        fees.accrue(1_000_000, 1_000_000, START).unwrap();
+
+        // The fee never exceeds the NAV
+        let accrual = fees.accrue(1_000_000, 1_000_000, i64::MAX).unwrap();
+        assert_eq!(accrual.management_fee, 1_000_000);
+        // A clock running backwards charges nothing
+        fees.accrue(1_000_000, 1_000_000, START).unwrap();
+        assert_eq!(
+            fees.accrue(1_000_000, 1_000_000, START - 1).unwrap(),
+            FeeAccrual::default()
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_NAV_ASSETS;
+
+/// A vault token account that counts towards the NAV, priced by `oracle`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct NavAsset {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub oracle: Pubkey,
+    /// Pyth feed id `oracle` must carry a price update for.
+    pub feed_id: [u8; 32],
+    pub decimals: u8,
+}
+
+/// Registered holdings of a vault and the last NAV computed from them, in the
+/// oracles' quote currency with `NAV_DECIMALS`.
+#[account]
+#[derive(InitSpace)]
+pub struct VaultNav {
+    pub vault: Pubkey,
+    #[max_len(MAX_NAV_ASSETS)]
+    pub assets: Vec<NavAsset>,
+    /// Oldest oracle price `compute_nav` accepts, in seconds.
+    pub max_price_age: u64,
+    /// Widest oracle confidence interval `compute_nav` accepts, in basis
+    /// points of the price.
+    pub max_confidence_bps: u16,
+    pub nav: u64,
+    /// Unix time of the last `compute_nav`, or zero since the assets changed.
+    pub updated_at: i64,
+    /// Unix time the assets were last set. Fees accrued before then were
+    /// charged against a different set of holdings.
+    pub assets_set_at: i64,
+    pub bump: u8,
+}
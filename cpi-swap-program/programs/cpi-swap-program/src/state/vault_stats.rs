@@ -0,0 +1,211 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{MAX_STATS_MINTS, PRICE_SCALE},
+    errors::CpiSwapError,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct MintStats {
+    pub mint: Pubkey,
+    /// Total spent from the vault in this mint.
+    pub volume_in: u64,
+    /// Total received by the vault in this mint.
+    pub volume_out: u64,
+    /// Total platform fees collected in this mint.
+    pub fees: u64,
+}
+
+/// Lifetime totals for a vault, updated by every vault-funded swap. Only the
+/// first [`MAX_STATS_MINTS`] mints traded get per-mint totals.
+#[account]
+#[derive(InitSpace, Debug, PartialEq, Eq)]
+pub struct VaultStats {
+    pub vault: Pubkey,
+    pub trade_count: u64,
+    pub last_slot: u64,
+    pub last_input_mint: Pubkey,
+    pub last_output_mint: Pubkey,
+    /// Output received per unit of input on the last trade, in raw token
+    /// units scaled by [`PRICE_SCALE`].
+    pub last_price: u64,
+    #[max_len(MAX_STATS_MINTS)]
+    pub mints: Vec<MintStats>,
+    pub bump: u8,
+}
+
+impl VaultStats {
+    /// Sets the vault and bump the first time the account is used.
+    pub fn init_if_needed(&mut self, vault: Pubkey, bump: u8) {
+        if self.vault == Pubkey::default() {
+            self.vault = vault;
+            self.bump = bump;
+        }
+    }
+
+    pub fn record_swap(
+        &mut self,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        amount_in: u64,
+        amount_out: u64,
+        slot: u64,
+    ) -> Result<()> {
+        self.trade_count = self
+            .trade_count
+            .checked_add(1)
+            .ok_or(CpiSwapError::MathOverflow)?;
+        self.last_slot = slot;
+        self.last_input_mint = input_mint;
+        self.last_output_mint = output_mint;
+        if amount_in > 0 {
+            self.last_price = (amount_out as u128 * PRICE_SCALE / amount_in as u128)
+                .try_into()
+                .unwrap_or(u64::MAX);
+        }
+
+        if let Some(stats) = self.mint_stats(input_mint) {
+            stats.volume_in = stats.volume_in.saturating_add(amount_in);
+        }
+        if let Some(stats) = self.mint_stats(output_mint) {
+            stats.volume_out = stats.volume_out.saturating_add(amount_out);
+        }
+
+        Ok(())
+    }
+
+    pub fn record_fee(&mut self, mint: Pubkey, amount: u64) {
+        if let Some(stats) = self.mint_stats(mint) {
+            stats.fees = stats.fees.saturating_add(amount);
+        }
+    }
+
+    fn mint_stats(&mut self, mint: Pubkey) -> Option<&mut MintStats> {
+        match self.mints.iter().position(|stats| stats.mint == mint) {
+            Some(index) => self.mints.get_mut(index),
+            None if self.mints.len() < MAX_STATS_MINTS => {
+                self.mints.push(MintStats {
+                    mint,
+                    volume_in: 0,
+                    volume_out: 0,
+                    fees: 0,
+                });
+                self.mints.last_mut()
+            }
+            None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::program::MAX_RETURN_DATA;
+
+    use super::*;
+
+    fn vault_stats() -> VaultStats {
+        VaultStats {
+            vault: Pubkey::default(),
+            trade_count: 0,
+            last_slot: 0,
+            last_input_mint: Pubkey::default(),
+            last_output_mint: Pubkey::default(),
+            last_price: 0,
+            mints: vec![],
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn record_swap_accumulates_per_mint() {
+        let mut stats = vault_stats();
+        let sol = Pubkey::new_unique();
+        let usdc = Pubkey::new_unique();
+
+        stats.record_swap(sol, usdc, 100, 2_000, 10).unwrap();
+        stats.record_swap(usdc, sol, 500, 25, 11).unwrap();
+        stats.record_fee(usdc, 7);
+
+        assert_eq!(stats.trade_count, 2);
+        assert_eq!(stats.last_slot, 11);
+        assert_eq!(stats.last_input_mint, usdc);
+        assert_eq!(stats.last_output_mint, sol);
+        assert_eq!(stats.last_price, (25 * PRICE_SCALE / 500) as u64);
+        assert_eq!(
+            stats.mints,
+            vec![
+                MintStats {
+                    mint: sol,
+                    volume_in: 100,
+                    volume_out: 25,
+                    fees: 0,
+                },
+                MintStats {
+                    mint: usdc,
+                    volume_in: 500,
+                    volume_out: 2_000,
+                    fees: 7,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn record_swap_saturates_volumes_and_fails_on_trade_count_overflow() {
+        let mut stats = vault_stats();
+        let sol = Pubkey::new_unique();
+        let usdc = Pubkey::new_unique();
+
+        stats.record_swap(sol, usdc, u64::MAX, 1, 0).unwrap();
+        stats.record_swap(sol, usdc, 1, u64::MAX, 0).unwrap();
+        stats.record_fee(usdc, u64::MAX);
+        stats.record_fee(usdc, 1);
+        assert_eq!(stats.mints[0].volume_in, u64::MAX);
+        assert_eq!(stats.mints[1].volume_out, u64::MAX);
+        assert_eq!(stats.mints[1].fees, u64::MAX);
+        // Output per unit of input above u64 is clamped
+        assert_eq!(stats.last_price, u64::MAX);
+
+        stats.trade_count = u64::MAX;
+        assert!(stats.record_swap(sol, usdc, 1, 1, 0).is_err());
+    }
+
+    #[test]
+    fn only_the_first_mints_get_totals() {
+        let mut stats = vault_stats();
+        let mints: Vec<Pubkey> = (0..=MAX_STATS_MINTS)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+
+        for pair in mints.chunks(2) {
+            stats
+                .record_swap(pair[0], *pair.last().unwrap(), 1, 1, 0)
+                .unwrap();
+        }
+        assert_eq!(stats.mints.len(), MAX_STATS_MINTS);
+
+        let untracked = mints[MAX_STATS_MINTS];
+        stats.record_swap(untracked, mints[0], 5, 5, 0).unwrap();
+        assert_eq!(stats.trade_count, 8);
+        assert_eq!(stats.last_input_mint, untracked);
+        assert!(stats.mints.iter().all(|stats| stats.mint != untracked));
+        assert_eq!(stats.mints[0].volume_out, 5);
+    }
+
+    #[test]
+    fn full_stats_fit_in_return_data() {
+        let mut stats = vault_stats();
+        stats.mints = (0..MAX_STATS_MINTS)
+            .map(|_| MintStats {
+                mint: Pubkey::new_unique(),
+                volume_in: u64::MAX,
+                volume_out: u64::MAX,
+                fees: u64::MAX,
+            })
+            .collect();
+
+        let size = stats.try_to_vec().unwrap().len();
+        assert_eq!(size, VaultStats::INIT_SPACE);
+        assert!(size <= MAX_RETURN_DATA);
+    }
+}
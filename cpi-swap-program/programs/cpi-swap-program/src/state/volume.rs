@@ -0,0 +1,226 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::CpiSwapError;
+
+/// Volume over a trailing window, tracked as the current fixed window plus
+/// the one before it. The previous window counts in proportion to how much of
+/// it the trailing window still covers, as if it had been spread evenly. A
+/// burst at the end of one window therefore still counts in full right after
+/// the boundary, instead of resetting to zero.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct SlidingVolume {
+    pub window_start: i64,
+    pub window_volume: u64,
+    pub previous_volume: u64,
+}
+
+impl SlidingVolume {
+    /// Adds `amount` at `now` and returns the volume over the trailing
+    /// `window_seconds`, including it.
+    pub fn record(&mut self, now: i64, window_seconds: i64, amount: u64) -> Result<u64> {
+        let window_start = now - now.rem_euclid(window_seconds);
+        if self.window_start != window_start {
+            // Only the window right before the current one still overlaps it
+            self.previous_volume = match window_start - self.window_start == window_seconds {
+                true => self.window_volume,
+                false => 0,
+            };
+            self.window_start = window_start;
+            self.window_volume = 0;
+        }
+        self.window_volume = self
+            .window_volume
+            .checked_add(amount)
+            .ok_or(CpiSwapError::MathOverflow)?;
+
+        let overlap = window_seconds - (now - window_start);
+        let carried = u128::from(self.previous_volume) * overlap as u128 / window_seconds as u128;
+        Ok(self.window_volume.saturating_add(carried as u64))
+    }
+}
+
+/// Input volume caps for one vault and one input mint, measured in raw token
+/// units over a trailing window of `window_seconds`.
+#[account]
+#[derive(InitSpace)]
+pub struct VolumeLimit {
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub window_seconds: i64,
+    pub max_vault_volume: u64,
+    pub max_operator_volume: u64,
+    pub volume: SlidingVolume,
+    pub bump: u8,
+}
+
+impl VolumeLimit {
+    pub fn record(&mut self, now: i64, amount: u64) -> Result<()> {
+        let volume = self.volume.record(now, self.window_seconds, amount)?;
+        require_gte!(
+            self.max_vault_volume,
+            volume,
+            CpiSwapError::VaultVolumeLimitExceeded
+        );
+        Ok(())
+    }
+
+    /// Records `amount` against the limit stored at `info`, the vault's
+    /// volume limit address for the input mint, and returns the updated limit.
+    /// Mints the admin never set a limit for have no account there and are
+    /// not capped, so this returns `None`. The address itself is checked by
+    /// the caller's seeds, so leaving the account out cannot skip a limit.
+    pub fn record_at(info: &AccountInfo, now: i64, amount: u64) -> Result<Option<Self>> {
+        if info.owner != &crate::ID {
+            return Ok(None);
+        }
+        let mut limit = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        limit.record(now, amount)?;
+        limit.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        Ok(Some(limit))
+    }
+}
+
+/// Input volume swapped by one operator against a [`VolumeLimit`].
+#[account]
+#[derive(InitSpace)]
+pub struct OperatorVolume {
+    pub operator: Pubkey,
+    pub volume_limit: Pubkey,
+    pub volume: SlidingVolume,
+    pub bump: u8,
+}
+
+impl OperatorVolume {
+    pub fn record(
+        &mut self,
+        now: i64,
+        amount: u64,
+        window_seconds: i64,
+        max_volume: u64,
+    ) -> Result<()> {
+        let volume = self.volume.record(now, window_seconds, amount)?;
+        require_gte!(
+            max_volume,
+            volume,
+            CpiSwapError::OperatorVolumeLimitExceeded
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: i64 = 3_600;
+
+    fn volume_limit(max_vault_volume: u64) -> VolumeLimit {
+        VolumeLimit {
+            vault: Pubkey::default(),
+            mint: Pubkey::default(),
+            window_seconds: WINDOW,
+            max_vault_volume,
+            max_operator_volume: 0,
+            volume: SlidingVolume::default(),
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn burst_across_a_window_boundary_is_limited() {
+        let mut limit = volume_limit(1_000);
+
+        limit.record(2 * WINDOW - 1, 1_000).unwrap();
+        // Right after the boundary the previous window still counts in full
+        assert!(limit.record(2 * WINDOW, 1).is_err());
+    }
+
+    #[test]
+    fn previous_window_decays_linearly() {
+        let mut volume = SlidingVolume::default();
+
+        assert_eq!(volume.record(WINDOW, WINDOW, 1_000).unwrap(), 1_000);
+        assert_eq!(volume.record(2 * WINDOW, WINDOW, 0).unwrap(), 1_000);
+        assert_eq!(
+            volume.record(2 * WINDOW + WINDOW / 4, WINDOW, 100).unwrap(),
+            850
+        );
+        assert_eq!(volume.record(3 * WINDOW - 1, WINDOW, 0).unwrap(), 100);
+        // The previous window is now the one with 100
+        assert_eq!(volume.record(3 * WINDOW, WINDOW, 0).unwrap(), 100);
+    }
+
+    #[test]
+    fn idle_window_clears_previous_volume() {
+        let mut volume = SlidingVolume::default();
+
+        volume.record(WINDOW, WINDOW, 1_000).unwrap();
+        assert_eq!(volume.record(3 * WINDOW, WINDOW, 10).unwrap(), 10);
+        assert_eq!(volume.previous_volume, 0);
+    }
+
+    #[test]
+    fn limits_fail_once_exceeded() {
+        let mut limit = volume_limit(1_000);
+        limit.record(WINDOW, 600).unwrap();
+        assert!(limit.record(WINDOW + 1, 401).is_err());
+
+        let mut operator_volume = OperatorVolume {
+            operator: Pubkey::default(),
+            volume_limit: Pubkey::default(),
+            volume: SlidingVolume::default(),
+            bump: 0,
+        };
+        operator_volume.record(WINDOW, 300, WINDOW, 500).unwrap();
+        // A failed swap reverts, so each attempt starts from the same state
+        assert!(operator_volume
+            .clone()
+            .record(WINDOW + 1, 201, WINDOW, 500)
+            .is_err());
+        operator_volume
+            .record(WINDOW + 1, 200, WINDOW, 500)
+            .unwrap();
+    }
+
+    #[test]
+    fn record_at_skips_unset_limits() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = vec![];
+        let system_program = Pubkey::default();
+        let unset = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &system_program,
+            false,
+            0,
+        );
+        assert!(VolumeLimit::record_at(&unset, WINDOW, u64::MAX)
+            .unwrap()
+            .is_none());
+
+        let mut lamports = 0;
+        let mut data = vec![];
+        volume_limit(1_000).try_serialize(&mut data).unwrap();
+        let set = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &crate::ID,
+            false,
+            0,
+        );
+        assert!(VolumeLimit::record_at(&set, WINDOW, 1_001).is_err());
+        let limit = VolumeLimit::record_at(&set, WINDOW, 600).unwrap().unwrap();
+        assert_eq!(limit.volume.window_volume, 600);
+        // The recorded volume is written back to the account
+        assert!(VolumeLimit::record_at(&set, WINDOW, 401).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        ed25519_program,
+        sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+    },
+};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{transfer_fee::TransferFeeAmount, BaseStateWithExtensions, StateWithExtensions},
+    state::{Account as SplTokenAccount, Mint as SplMint},
+};
+
+use crate::errors::CpiSwapError;
+
+/// Unpacks `account` if it is an initialized SPL Token or Token-2022 token
+/// account, ignoring any extensions.
+pub fn unpack_token_account(account: &AccountInfo) -> Option<SplTokenAccount> {
+    if account.owner != &anchor_spl::token::ID && account.owner != &anchor_spl::token_2022::ID {
+        return None;
+    }
+
+    let data = account.try_borrow_data().ok()?;
+    StateWithExtensions::<SplTokenAccount>::unpack(&data)
+        .ok()
+        .map(|state| state.base)
+}
+
+/// Returns the Token-2022 transfer fees withheld in `account`, which must be
+/// harvested to the mint before the account can be closed.
+pub fn withheld_transfer_fees(account: &AccountInfo) -> u64 {
+    if account.owner != &anchor_spl::token_2022::ID {
+        return 0;
+    }
+
+    let Ok(data) = account.try_borrow_data() else {
+        return 0;
+    };
+    StateWithExtensions::<SplTokenAccount>::unpack(&data)
+        .ok()
+        .and_then(|state| {
+            state
+                .get_extension::<TransferFeeAmount>()
+                .ok()
+                .map(|fee| u64::from(fee.withheld_amount))
+        })
+        .unwrap_or(0)
+}
+
+/// Unpacks `account` if it is an initialized SPL Token or Token-2022 mint.
+pub fn unpack_mint(account: &AccountInfo) -> Option<SplMint> {
+    if account.owner != &anchor_spl::token::ID && account.owner != &anchor_spl::token_2022::ID {
+        return None;
+    }
+
+    let data = account.try_borrow_data().ok()?;
+    StateWithExtensions::<SplMint>::unpack(&data)
+        .ok()
+        .map(|state| state.base)
+}
+
+/// Returns the owner and delegate of `account` if it is an initialized SPL
+/// Token or Token-2022 token account.
+pub fn token_account_authorities(account: &AccountInfo) -> Option<(Pubkey, Option<Pubkey>)> {
+    unpack_token_account(account)
+        .map(|token_account| (token_account.owner, token_account.delegate.into()))
+}
+
+/// Size of the `Ed25519SignatureOffsets` entry that follows the two byte
+/// header of an ed25519 precompile instruction.
+const ED25519_OFFSETS_SIZE: usize = 14;
+
+/// Checks that the instruction right before the current one is an ed25519
+/// precompile verification of exactly one `signer` signature over `message`,
+/// with all of its data inlined in that instruction.
+pub fn verify_ed25519_instruction(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    let ed25519_index = current_index
+        .checked_sub(1)
+        .ok_or(CpiSwapError::InvalidSignatureInstruction)?;
+    let ix = load_instruction_at_checked(ed25519_index as usize, instructions_sysvar)?;
+
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        CpiSwapError::InvalidSignatureInstruction
+    );
+    require!(
+        ix.data.len() >= 2 + ED25519_OFFSETS_SIZE && ix.data[0] == 1,
+        CpiSwapError::InvalidSignatureInstruction
+    );
+
+    let offsets = &ix.data[2..2 + ED25519_OFFSETS_SIZE];
+    let read_u16 = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+    let public_key_offset = read_u16(4) as usize;
+    let message_offset = read_u16(8) as usize;
+    let message_size = read_u16(10) as usize;
+
+    // Instruction indices of u16::MAX point at the precompile instruction
+    // itself; anything else lets the signed bytes live elsewhere.
+    require!(
+        read_u16(2) == u16::MAX && read_u16(6) == u16::MAX && read_u16(12) == u16::MAX,
+        CpiSwapError::InvalidSignatureInstruction
+    );
+
+    let signed_key = ix
+        .data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(CpiSwapError::InvalidSignatureInstruction)?;
+    let signed_message = ix
+        .data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(CpiSwapError::InvalidSignatureInstruction)?;
+
+    require!(
+        signed_key == signer.as_ref(),
+        CpiSwapError::InvalidIntentSignature
+    );
+    require!(
+        signed_message == message,
+        CpiSwapError::InvalidIntentSignature
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::sysvar::{
+        self,
+        instructions::{construct_instructions_data, store_current_index, BorrowedInstruction},
+    };
+
+    use super::*;
+
+    const PUBLIC_KEY_OFFSET: u16 = 16;
+    const SIGNATURE_OFFSET: u16 = PUBLIC_KEY_OFFSET + 32;
+    const MESSAGE_OFFSET: u16 = SIGNATURE_OFFSET + 64;
+
+    /// Precompile data verifying one signature with everything inlined. The
+    /// signature bytes are left zero since the precompile, not the program,
+    /// checks them.
+    fn ed25519_data(signer: &Pubkey, message: &[u8]) -> Vec<u8> {
+        let mut data = vec![1, 0];
+        for field in [
+            SIGNATURE_OFFSET,
+            u16::MAX,
+            PUBLIC_KEY_OFFSET,
+            u16::MAX,
+            MESSAGE_OFFSET,
+            message.len() as u16,
+            u16::MAX,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[0; 64]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    /// Runs `verify_ed25519_instruction` from an instruction placed right
+    /// after one calling `program_id` with `data`.
+    fn verify(program_id: &Pubkey, data: &[u8], signer: &Pubkey, message: &[u8]) -> Result<()> {
+        let mut sysvar_data = construct_instructions_data(&[
+            BorrowedInstruction {
+                program_id,
+                accounts: vec![],
+                data,
+            },
+            BorrowedInstruction {
+                program_id: &crate::ID,
+                accounts: vec![],
+                data: &[],
+            },
+        ]);
+        store_current_index(&mut sysvar_data, 1);
+
+        let mut lamports = 0;
+        let instructions_sysvar = AccountInfo::new(
+            &sysvar::instructions::ID,
+            false,
+            false,
+            &mut lamports,
+            &mut sysvar_data,
+            &sysvar::ID,
+            false,
+            0,
+        );
+        verify_ed25519_instruction(&instructions_sysvar, signer, message)
+    }
+
+    #[test]
+    fn accepts_an_inlined_signature() {
+        let signer = Pubkey::new_unique();
+        let data = ed25519_data(&signer, b"intent");
+        verify(&ed25519_program::ID, &data, &signer, b"intent").unwrap();
+    }
+
+    #[test]
+    fn rejects_another_signer_or_message() {
+        let signer = Pubkey::new_unique();
+        let data = ed25519_data(&signer, b"intent");
+        assert!(verify(
+            &ed25519_program::ID,
+            &data,
+            &Pubkey::new_unique(),
+            b"intent"
+        )
+        .is_err());
+        assert!(verify(&ed25519_program::ID, &data, &signer, b"other!").is_err());
+    }
+
+    #[test]
+    fn rejects_other_programs_and_signature_counts() {
+        let signer = Pubkey::new_unique();
+        let mut data = ed25519_data(&signer, b"intent");
+        assert!(verify(&Pubkey::new_unique(), &data, &signer, b"intent").is_err());
+
+        data[0] = 2;
+        assert!(verify(&ed25519_program::ID, &data, &signer, b"intent").is_err());
+    }
+
+    #[test]
+    fn rejects_data_in_other_instructions() {
+        let signer = Pubkey::new_unique();
+        // Signature, public key and message instruction indices
+        for at in [4, 8, 14] {
+            let mut data = ed25519_data(&signer, b"intent");
+            data[at..at + 2].copy_from_slice(&1u16.to_le_bytes());
+            assert!(verify(&ed25519_program::ID, &data, &signer, b"intent").is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_offsets_out_of_range() {
+        let signer = Pubkey::new_unique();
+        let data = ed25519_data(&signer, b"intent");
+
+        let mut public_key_past_end = data.clone();
+        public_key_past_end[6..8].copy_from_slice(&(data.len() as u16 - 31).to_le_bytes());
+        assert!(verify(
+            &ed25519_program::ID,
+            &public_key_past_end,
+            &signer,
+            b"intent"
+        )
+        .is_err());
+
+        let mut message_past_end = data.clone();
+        message_past_end[12..14].copy_from_slice(&7u16.to_le_bytes());
+        assert!(verify(&ed25519_program::ID, &message_past_end, &signer, b"intent").is_err());
+
+        assert!(verify(&ed25519_program::ID, &data[..10], &signer, b"intent").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+import { BN } from "@coral-xyz/anchor";
 import { QuoteResponse, SwapInstructionsResponse } from "@jup-ag/api";
 import {
   AccountMeta,
@@ -26,8 +27,13 @@ export async function swap() {
   const quote = await getQuote();
   const quoteReverse = await getQuoteReverse(quote.outAmount);
 
+  const strategyId = new BN(0).toArrayLike(Buffer, "le", 8);
+  const [strategyAddress] = PublicKey.findProgramAddressSync(
+    [Buffer.from("strategy"), strategyId],
+    new PublicKey(CPI_SWAP_PROGRAM_ID)
+  );
   const [vaultAddress] = PublicKey.findProgramAddressSync(
-    [Buffer.from("vault")],
+    [Buffer.from("vault"), strategyId],
     new PublicKey(CPI_SWAP_PROGRAM_ID)
   );
 
@@ -96,14 +102,16 @@ export async function swap() {
   const swapInstruction = await program.methods
     .swap(Buffer.from(swapIxResponse.swapInstruction.data, "base64"))
     .accountsPartial({
+      operator: wallet.publicKey,
       inputMint: new PublicKey(SOL_MINT),
       inputMintProgram: TOKEN_PROGRAM_ID,
       outputMint: new PublicKey(USDC_MINT),
       outputMintProgram: TOKEN_PROGRAM_ID,
+      strategy: strategyAddress,
       vault: vaultAddress,
       vaultInputTokenAccount: solAta,
       vaultOutputTokenAccount: usdcAta,
-      jupiterProgram: new PublicKey(JUPITER_PROGRAM_ID),
+      aggregatorProgram: new PublicKey(JUPITER_PROGRAM_ID),
     })
     .remainingAccounts(remainingAccounts)
     .instruction();
@@ -111,14 +119,16 @@ export async function swap() {
   const swapReverseInstruction = await program.methods
     .swap(Buffer.from(swapReverseIxResponse.swapInstruction.data, "base64"))
     .accountsPartial({
+      operator: wallet.publicKey,
       inputMint: new PublicKey(USDC_MINT),
       inputMintProgram: TOKEN_PROGRAM_ID,
       outputMint: new PublicKey(SOL_MINT),
       outputMintProgram: TOKEN_PROGRAM_ID,
+      strategy: strategyAddress,
       vault: vaultAddress,
       vaultInputTokenAccount: usdcAta,
       vaultOutputTokenAccount: solAta,
-      jupiterProgram: new PublicKey(JUPITER_PROGRAM_ID),
+      aggregatorProgram: new PublicKey(JUPITER_PROGRAM_ID),
     })
     .remainingAccounts(remainingAccountsReverse)
     .instruction();